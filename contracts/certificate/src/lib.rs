@@ -1,11 +1,22 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, Env, Vec, panic_with_error};
+// `issue_certificate` takes every certificate field as its own argument so
+// other contracts can call it without sharing types.
+#![allow(clippy::too_many_arguments)]
+mod test;
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, panic_with_error, xdr::ToXdr, Address, Bytes, BytesN,
+    Env, Symbol, Vec,
+};
 
 #[derive(Clone)]
 #[contracttype]
 pub enum Error {
     CertificateNotFound,
     Unauthorized,
+    RootNotFound,
+    InvalidLeafCount,
+    BatchTooLarge,
 }
 
 impl From<Error> for soroban_sdk::Error {
@@ -14,6 +25,21 @@ impl From<Error> for soroban_sdk::Error {
     }
 }
 
+/// Upper bound on certificates issued by one `issue_certificates_batch`
+/// call so a call stays within the ledger's per-transaction resource
+/// limits. Larger cohorts are issued in several chunks.
+pub const MAX_ISSUE_BATCH: u32 = 50;
+
+/// Honours awarded on top of a passing grade.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Honours {
+    None,
+    Merit,
+    Distinction,
+    HighDistinction,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct Certificate {
@@ -23,6 +49,74 @@ pub struct Certificate {
     pub institution: Address,
     pub issued_at: u64,
     pub metadata: Bytes,
+    pub course_version: u32,
+    pub score: u32,
+    pub honours: Honours,
+    pub content_hash: BytesN<32>,
+    pub status: bool,
+}
+
+/// Per-student input to `issue_certificates_batch`.
+#[derive(Clone)]
+#[contracttype]
+pub struct CertificateRequest {
+    pub student: Address,
+    pub metadata: Bytes,
+    pub score: u32,
+    pub honours: Honours,
+    pub content_hash: BytesN<32>,
+}
+
+/// Certificate that keeps the student and metadata off the ledger. Only a
+/// salted commitment is stored; see `compute_commitment`.
+#[derive(Clone)]
+#[contracttype]
+pub struct PrivateCertificate {
+    pub id: u64,
+    pub commitment: BytesN<32>,
+    pub course_id: u64,
+    pub institution: Address,
+    pub issued_at: u64,
+    pub status: bool,
+}
+
+/// Certificate whose fields are committed to as separate Merkle leaves so
+/// the holder can disclose any subset of them; see `compute_field_leaf`.
+#[derive(Clone)]
+#[contracttype]
+pub struct DisclosableCertificate {
+    pub id: u64,
+    pub fields_root: BytesN<32>,
+    pub field_count: u32,
+    pub institution: Address,
+    pub issued_at: u64,
+    pub status: bool,
+}
+
+/// A field revealed by the holder, with the sibling hashes from its leaf up
+/// to the certificate's `fields_root`.
+#[derive(Clone)]
+#[contracttype]
+pub struct DisclosedField {
+    pub name: Symbol,
+    pub value: Bytes,
+    pub salt: BytesN<32>,
+    pub proof: Vec<BytesN<32>>,
+}
+
+/// Merkle root of a batch of certificate hashes anchored by an institution
+/// in place of one storage entry per certificate. `status` turns false when
+/// the whole batch is revoked; single leaves are revoked separately with
+/// `revoke_anchored_leaf`.
+#[derive(Clone)]
+#[contracttype]
+pub struct AnchoredRoot {
+    pub id: u64,
+    pub institution: Address,
+    pub course_id: u64,
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    pub anchored_at: u64,
     pub status: bool,
 }
 
@@ -37,42 +131,296 @@ impl CertificateContract {
         course_id: u64,
         institution: Address,
         metadata: Bytes,
+        course_version: u32,
+        score: u32,
+        honours: Honours,
+        content_hash: BytesN<32>,
     ) -> u64 {
         // Verify that the caller is the institution
         institution.require_auth();
 
-        // Generate a unique certificate ID
-        let id: u64 = env.ledger().sequence().into();
+        Self::store_certificate(
+            &env,
+            CertificateRequest {
+                student,
+                metadata,
+                score,
+                honours,
+                content_hash,
+            },
+            course_id,
+            &institution,
+            course_version,
+        )
+    }
 
-        // Create new certificate
-        let certificate = Certificate {
+    /// Issues one certificate per request for the same course. The batch
+    /// is all-or-nothing; the returned ids follow the order of `requests`.
+    /// At most `MAX_ISSUE_BATCH` requests are accepted per call.
+    pub fn issue_certificates_batch(
+        env: Env,
+        institution: Address,
+        course_id: u64,
+        course_version: u32,
+        requests: Vec<CertificateRequest>,
+    ) -> Vec<u64> {
+        institution.require_auth();
+
+        if requests.len() > MAX_ISSUE_BATCH {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+
+        let mut ids = Vec::new(&env);
+        for request in requests.iter() {
+            let id = Self::store_certificate(&env, request, course_id, &institution, course_version);
+            ids.push_back(id);
+        }
+
+        ids
+    }
+
+    /// Issues a certificate that only records `commitment`, so the holder's
+    /// identity and metadata are not readable from storage. Ids are shared
+    /// with public certificates.
+    pub fn issue_private_certificate(
+        env: Env,
+        institution: Address,
+        course_id: u64,
+        commitment: BytesN<32>,
+    ) -> u64 {
+        institution.require_auth();
+
+        let id = Self::next_certificate_id(&env);
+        let certificate = PrivateCertificate {
             id,
-            student: student.clone(),
+            commitment,
             course_id,
             institution,
-            issued_at: env.ledger().timestamp().into(),
-            metadata,
+            issued_at: env.ledger().timestamp(),
             status: true,
         };
+        env.storage()
+            .persistent()
+            .set(&Self::get_private_certificate_key(&env, id), &certificate);
 
-        // Store the certificate
-        env.storage().persistent().set(&id, &certificate);
+        id
+    }
 
-        // Add to student's certificates list
-        let mut student_certs = Self::get_student_certificates(&env, &student);
-        student_certs.push_back(id);
-        env.storage().persistent().set(&student, &student_certs);
+    pub fn get_private_certificate(env: Env, id: u64) -> PrivateCertificate {
+        env.storage()
+            .persistent()
+            .get(&Self::get_private_certificate_key(&env, id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))
+    }
+
+    /// Checks a claim against a private certificate. The holder reveals the
+    /// salt together with the student and metadata they committed to.
+    /// Revoked certificates never verify.
+    pub fn verify_private_certificate(
+        env: Env,
+        id: u64,
+        student: Address,
+        metadata: Bytes,
+        salt: BytesN<32>,
+    ) -> bool {
+        let certificate = Self::get_private_certificate(env.clone(), id);
+
+        certificate.status
+            && compute_commitment(&env, &salt, &student, &metadata) == certificate.commitment
+    }
+
+    /// Issues a certificate that only records the Merkle root over its
+    /// field leaves. The tree is built like the batch trees of
+    /// `anchor_batch_root`. Ids are shared with public certificates.
+    pub fn issue_disclosable_certificate(
+        env: Env,
+        institution: Address,
+        fields_root: BytesN<32>,
+        field_count: u32,
+    ) -> u64 {
+        institution.require_auth();
+
+        if field_count == 0 {
+            panic_with_error!(&env, Error::InvalidLeafCount);
+        }
+
+        let id = Self::next_certificate_id(&env);
+        let certificate = DisclosableCertificate {
+            id,
+            fields_root,
+            field_count,
+            institution,
+            issued_at: env.ledger().timestamp(),
+            status: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&Self::get_disclosable_certificate_key(&env, id), &certificate);
 
         id
     }
 
+    pub fn get_disclosable_certificate(env: Env, id: u64) -> DisclosableCertificate {
+        env.storage()
+            .persistent()
+            .get(&Self::get_disclosable_certificate_key(&env, id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))
+    }
+
+    /// Returns true only if the certificate has not been revoked and every
+    /// disclosed field belongs to it. Disclosing nothing proves nothing and
+    /// returns false.
+    pub fn verify_disclosed_fields(env: Env, id: u64, fields: Vec<DisclosedField>) -> bool {
+        let certificate = Self::get_disclosable_certificate(env.clone(), id);
+
+        if !certificate.status || fields.is_empty() {
+            return false;
+        }
+
+        let depth = merkle_depth(certificate.field_count);
+        fields.iter().all(|field| {
+            if field.proof.len() != depth {
+                return false;
+            }
+            let leaf = compute_field_leaf(&env, &field.name, &field.value, &field.salt);
+            compute_merkle_root(&env, leaf, &field.proof) == certificate.fields_root
+        })
+    }
+
     pub fn verify_certificate(env: Env, id: u64) -> Certificate {
         env.storage().persistent()
             .get(&id)
             .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))
     }
 
+    /// Checks that the certificate has not been revoked and that the
+    /// SHA-256 of its document matches the hash anchored at issuance.
+    pub fn verify_document_hash(env: Env, id: u64, hash: BytesN<32>) -> bool {
+        let certificate: Certificate = env.storage().persistent()
+            .get(&id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        certificate.status && certificate.content_hash == hash
+    }
+
+    /// Anchors the Merkle root of a batch of certificate hashes. Each leaf
+    /// node is `sha256(0x00 || document hash)` and each parent is
+    /// `sha256(0x01 || lower child || higher child)`. An unpaired node is
+    /// hashed with itself, so every proof has `ceil(log2(leaf_count))` steps.
+    pub fn anchor_batch_root(
+        env: Env,
+        institution: Address,
+        course_id: u64,
+        root: BytesN<32>,
+        leaf_count: u32,
+    ) -> u64 {
+        institution.require_auth();
+
+        if leaf_count == 0 {
+            panic_with_error!(&env, Error::InvalidLeafCount);
+        }
+
+        let next_id_key = Bytes::from_slice(&env, b"next_root_id");
+        let id: u64 = env.storage().persistent().get(&next_id_key).unwrap_or(1);
+        env.storage().persistent().set(&next_id_key, &(id + 1));
+
+        let anchored_root = AnchoredRoot {
+            id,
+            institution,
+            course_id,
+            root,
+            leaf_count,
+            anchored_at: env.ledger().timestamp(),
+            status: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&Self::get_anchored_root_key(&env, id), &anchored_root);
+
+        id
+    }
+
+    pub fn get_anchored_root(env: Env, root_id: u64) -> AnchoredRoot {
+        env.storage()
+            .persistent()
+            .get(&Self::get_anchored_root_key(&env, root_id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::RootNotFound))
+    }
+
+    /// Revokes every certificate in the batch anchored as `root_id`. Only
+    /// the anchoring institution can revoke it.
+    pub fn revoke_batch_root(env: Env, root_id: u64) {
+        let mut anchored_root = Self::get_anchored_root(env.clone(), root_id);
+        anchored_root.institution.require_auth();
+
+        anchored_root.status = false;
+        env.storage()
+            .persistent()
+            .set(&Self::get_anchored_root_key(&env, root_id), &anchored_root);
+    }
+
+    /// Revokes the single certificate whose document hash is `leaf` in the
+    /// batch anchored as `root_id`. Only the anchoring institution can
+    /// revoke it.
+    pub fn revoke_anchored_leaf(env: Env, root_id: u64, leaf: BytesN<32>) {
+        let anchored_root = Self::get_anchored_root(env.clone(), root_id);
+        anchored_root.institution.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Self::get_revoked_leaf_key(&env, root_id, &leaf), &true);
+    }
+
+    /// Checks that the document hash `leaf` belongs to the batch anchored as
+    /// `root_id`, given the sibling hashes from the leaf up to the root.
+    /// Leaves of a revoked batch and revoked leaves never verify.
+    pub fn verify_inclusion(
+        env: Env,
+        root_id: u64,
+        leaf: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> bool {
+        let anchored_root = Self::get_anchored_root(env.clone(), root_id);
+
+        if !anchored_root.status || proof.len() != merkle_depth(anchored_root.leaf_count) {
+            return false;
+        }
+
+        let revoked_leaf_key = Self::get_revoked_leaf_key(&env, root_id, &leaf);
+        if env.storage().persistent().has(&revoked_leaf_key) {
+            return false;
+        }
+
+        compute_merkle_root(&env, leaf, &proof) == anchored_root.root
+    }
+
+    /// Revokes a public, private or disclosable certificate. All kinds share
+    /// one id sequence, so `id` identifies exactly one of them.
     pub fn revoke_certificate(env: Env, id: u64) {
+        let disclosable_key = Self::get_disclosable_certificate_key(&env, id);
+        if let Some(mut certificate) = env
+            .storage()
+            .persistent()
+            .get::<Bytes, DisclosableCertificate>(&disclosable_key)
+        {
+            certificate.institution.require_auth();
+            certificate.status = false;
+            env.storage().persistent().set(&disclosable_key, &certificate);
+            return;
+        }
+
+        let private_key = Self::get_private_certificate_key(&env, id);
+        if let Some(mut certificate) = env
+            .storage()
+            .persistent()
+            .get::<Bytes, PrivateCertificate>(&private_key)
+        {
+            certificate.institution.require_auth();
+            certificate.status = false;
+            env.storage().persistent().set(&private_key, &certificate);
+            return;
+        }
+
         // Get the certificate
         let mut certificate: Certificate = env.storage().persistent()
             .get(&id)
@@ -101,8 +449,154 @@ impl CertificateContract {
         certificates
     }
 
+    fn store_certificate(
+        env: &Env,
+        request: CertificateRequest,
+        course_id: u64,
+        institution: &Address,
+        course_version: u32,
+    ) -> u64 {
+        let id = Self::next_certificate_id(env);
+
+        // Create new certificate
+        let certificate = Certificate {
+            id,
+            student: request.student.clone(),
+            course_id,
+            institution: institution.clone(),
+            issued_at: env.ledger().timestamp(),
+            metadata: request.metadata,
+            course_version,
+            score: request.score,
+            honours: request.honours,
+            content_hash: request.content_hash,
+            status: true,
+        };
+
+        // Store the certificate
+        env.storage().persistent().set(&id, &certificate);
+
+        // Add to student's certificates list
+        let mut student_certs = Self::get_student_certificates(env, &request.student);
+        student_certs.push_back(id);
+        env.storage().persistent().set(&request.student, &student_certs);
+
+        id
+    }
+
+    // Generate a unique certificate ID. Ledger sequence numbers repeat
+    // within a ledger, so a counter keeps batch issuance collision free.
+    fn next_certificate_id(env: &Env) -> u64 {
+        let next_id_key = Bytes::from_slice(env, b"next_certificate_id");
+        let id: u64 = env.storage().persistent().get(&next_id_key).unwrap_or(1);
+        env.storage().persistent().set(&next_id_key, &(id + 1));
+        id
+    }
+
+    fn get_private_certificate_key(env: &Env, id: u64) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"private_certificate_"));
+        key.append(&Bytes::from_array(env, &id.to_le_bytes()));
+        key
+    }
+
+    fn get_disclosable_certificate_key(env: &Env, id: u64) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"disclosable_certificate_"));
+        key.append(&Bytes::from_array(env, &id.to_le_bytes()));
+        key
+    }
+
+    fn get_anchored_root_key(env: &Env, root_id: u64) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"anchored_root_"));
+        key.append(&Bytes::from_array(env, &root_id.to_le_bytes()));
+        key
+    }
+
+    fn get_revoked_leaf_key(env: &Env, root_id: u64, leaf: &BytesN<32>) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"revoked_leaf_"));
+        key.append(&Bytes::from_array(env, &root_id.to_le_bytes()));
+        key.append(&leaf.clone().into());
+        key
+    }
+
     // Helper function to get student's certificates IDs
     fn get_student_certificates(env: &Env, student: &Address) -> Vec<u64> {
         env.storage().persistent().get(student).unwrap_or_else(|| Vec::new(env))
     }
-}
\ No newline at end of file
+}
+
+/// Commitment stored by `issue_private_certificate`:
+/// SHA-256 of `salt || student XDR || metadata`.
+pub fn compute_commitment(
+    env: &Env,
+    salt: &BytesN<32>,
+    student: &Address,
+    metadata: &Bytes,
+) -> BytesN<32> {
+    let mut preimage = Bytes::new(env);
+    preimage.append(&salt.clone().into());
+    preimage.append(&student.clone().to_xdr(env));
+    preimage.append(metadata);
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+/// Leaf for each field of a disclosable certificate: SHA-256 of
+/// `salt || name XDR || value`, hashed into a leaf node like a document
+/// hash. Salting each field keeps undisclosed low-entropy values such as
+/// grades from being guessed.
+pub fn compute_field_leaf(
+    env: &Env,
+    name: &Symbol,
+    value: &Bytes,
+    salt: &BytesN<32>,
+) -> BytesN<32> {
+    let mut preimage = Bytes::new(env);
+    preimage.append(&salt.clone().into());
+    preimage.append(&name.clone().to_xdr(env));
+    preimage.append(value);
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+// Number of proof steps from a leaf to the root of a tree with
+// `leaf_count` leaves, i.e. ceil(log2(leaf_count)).
+fn merkle_depth(leaf_count: u32) -> u32 {
+    if leaf_count <= 1 {
+        return 0;
+    }
+    u32::BITS - (leaf_count - 1).leading_zeros()
+}
+
+// Leaves and parents are hashed under different prefixes so an internal node
+// can never be passed off as a leaf.
+fn merkle_leaf_hash(env: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &[0x00]);
+    preimage.append(&leaf.clone().into());
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+fn merkle_node_hash(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &[0x01]);
+    if a <= b {
+        preimage.append(&a.clone().into());
+        preimage.append(&b.clone().into());
+    } else {
+        preimage.append(&b.clone().into());
+        preimage.append(&a.clone().into());
+    }
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+// Folds a Merkle proof into the root it implies for `leaf`. Pairs are hashed
+// in ascending order so proofs do not need to carry left/right positions.
+fn compute_merkle_root(env: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut computed = merkle_leaf_hash(env, &leaf);
+
+    for sibling in proof.iter() {
+        computed = merkle_node_hash(env, &computed, &sibling);
+    }
+
+    computed
+}
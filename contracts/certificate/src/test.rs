@@ -1,24 +1,26 @@
 #![cfg(test)]
+extern crate std;
 
 use super::*;
-use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{vec, Env};
 
 #[test]
 fn test_certificate_lifecycle() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, CertificateContract);
-    let client = CertificateContract::new(&env, &contract_id);
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
 
     // Setup test accounts
     let student = Address::generate(&env);
     let institution = Address::generate(&env);
     let course_id = 1u64;
     let metadata = Bytes::from_array(&env, &[1, 2, 3]);
+    let document_hash = env.crypto().sha256(&metadata).to_bytes();
 
     // Test certificate issuance
     env.mock_all_auths();
-    let cert_id = client.issue_certificate(&student, &course_id, &institution, &metadata);
+    let cert_id = client.issue_certificate(&student, &course_id, &institution, &metadata, &1u32, &100u32, &Honours::None, &document_hash);
     
     // Test certificate verification
     let cert = client.verify_certificate(&cert_id);
@@ -26,7 +28,8 @@ fn test_certificate_lifecycle() {
     assert_eq!(cert.course_id, course_id);
     assert_eq!(cert.institution, institution);
     assert_eq!(cert.metadata, metadata);
-    assert_eq!(cert.status, true);
+    assert!(cert.status);
+    assert_eq!(cert.content_hash, document_hash);
 
     // Test listing certificates
     let certs = client.list_certificates(&student);
@@ -37,14 +40,14 @@ fn test_certificate_lifecycle() {
     env.mock_all_auths();
     client.revoke_certificate(&cert_id);
     let revoked_cert = client.verify_certificate(&cert_id);
-    assert_eq!(revoked_cert.status, false);
+    assert!(!revoked_cert.status);
 }
 
 #[test]
 fn test_multiple_certificates() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, CertificateContract);
-    let client = CertificateContract::new(&env, &contract_id);
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
 
     let student = Address::generate(&env);
     let institution = Address::generate(&env);
@@ -57,6 +60,10 @@ fn test_multiple_certificates() {
         &1u64,
         &institution,
         &Bytes::from_array(&env, &[1]),
+        &1u32,
+        &100u32,
+        &Honours::None,
+        &BytesN::from_array(&env, &[0; 32]),
     );
     
     let cert_id2 = client.issue_certificate(
@@ -64,6 +71,10 @@ fn test_multiple_certificates() {
         &2u64,
         &institution,
         &Bytes::from_array(&env, &[2]),
+        &1u32,
+        &100u32,
+        &Honours::None,
+        &BytesN::from_array(&env, &[0; 32]),
     );
 
     // Verify student has multiple certificates
@@ -71,6 +82,327 @@ fn test_multiple_certificates() {
     assert_eq!(certs.len(), 2);
     
     // Verify both certificates are valid
-    assert_eq!(client.verify_certificate(&cert_id1).status, true);
-    assert_eq!(client.verify_certificate(&cert_id2).status, true);
-}
\ No newline at end of file
+    assert!(client.verify_certificate(&cert_id1).status);
+    assert!(client.verify_certificate(&cert_id2).status);
+}
+
+#[test]
+fn test_batch_issuance() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let requests = vec![
+        &env,
+        CertificateRequest {
+            student: first.clone(),
+            metadata: Bytes::from_array(&env, &[1]),
+            score: 70,
+            honours: Honours::None,
+            content_hash: BytesN::from_array(&env, &[1; 32]),
+        },
+        CertificateRequest {
+            student: second.clone(),
+            metadata: Bytes::from_array(&env, &[2]),
+            score: 95,
+            honours: Honours::HighDistinction,
+            content_hash: BytesN::from_array(&env, &[2; 32]),
+        },
+    ];
+
+    let ids = client.issue_certificates_batch(&institution, &7u64, &2u32, &requests);
+    assert_eq!(ids.len(), 2);
+    assert_ne!(ids.get(0).unwrap(), ids.get(1).unwrap());
+
+    let cert = client.verify_certificate(&ids.get(1).unwrap());
+    assert_eq!(cert.student, second);
+    assert_eq!(cert.course_id, 7);
+    assert_eq!(cert.course_version, 2);
+    assert_eq!(cert.score, 95);
+    assert_eq!(cert.honours, Honours::HighDistinction);
+
+    assert_eq!(client.list_certificates(&first).len(), 1);
+    assert_eq!(client.list_certificates(&second).len(), 1);
+
+    // Cohorts above the cap have to be issued in chunks
+    let mut oversized = Vec::new(&env);
+    for _ in 0..=MAX_ISSUE_BATCH {
+        oversized.push_back(requests.get(0).unwrap());
+    }
+    let result = client.try_issue_certificates_batch(&institution, &7u64, &2u32, &oversized);
+    assert!(result.is_err());
+    assert_eq!(client.list_certificates(&first).len(), 1);
+}
+
+#[test]
+fn test_verify_document_hash() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let student = Address::generate(&env);
+    let institution = Address::generate(&env);
+    let document = Bytes::from_slice(&env, b"%PDF-1.7 certificate of completion");
+    let document_hash = env.crypto().sha256(&document).to_bytes();
+
+    env.mock_all_auths();
+    let cert_id = client.issue_certificate(
+        &student,
+        &1u64,
+        &institution,
+        &Bytes::from_slice(&env, b"ipfs://QmCertificate"),
+        &1u32,
+        &88u32,
+        &Honours::Merit,
+        &document_hash,
+    );
+
+    assert!(client.verify_document_hash(&cert_id, &document_hash));
+
+    let tampered = Bytes::from_slice(&env, b"%PDF-1.7 certificate of excellence");
+    let tampered_hash = env.crypto().sha256(&tampered).to_bytes();
+    assert!(!client.verify_document_hash(&cert_id, &tampered_hash));
+
+    // A revoked certificate's genuine document no longer verifies
+    client.revoke_certificate(&cert_id);
+    assert!(!client.verify_document_hash(&cert_id, &document_hash));
+}
+
+fn hash_leaf(env: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &[0x00]);
+    preimage.append(&leaf.clone().into());
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let mut pair = Bytes::from_array(env, &[0x01]);
+    if a <= b {
+        pair.append(&a.clone().into());
+        pair.append(&b.clone().into());
+    } else {
+        pair.append(&b.clone().into());
+        pair.append(&a.clone().into());
+    }
+    env.crypto().sha256(&pair).to_bytes()
+}
+
+#[test]
+fn test_merkle_batch_anchoring() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+
+    let leaves: std::vec::Vec<BytesN<32>> = (0u8..4)
+        .map(|i| env.crypto().sha256(&Bytes::from_array(&env, &[i; 8])).to_bytes())
+        .collect();
+    let nodes: std::vec::Vec<BytesN<32>> = leaves.iter().map(|leaf| hash_leaf(&env, leaf)).collect();
+    let left = hash_pair(&env, &nodes[0], &nodes[1]);
+    let right = hash_pair(&env, &nodes[2], &nodes[3]);
+    let root = hash_pair(&env, &left, &right);
+
+    env.mock_all_auths();
+    let root_id = client.anchor_batch_root(&institution, &5u64, &root, &4u32);
+
+    let anchored = client.get_anchored_root(&root_id);
+    assert_eq!(anchored.root, root);
+    assert_eq!(anchored.institution, institution);
+    assert_eq!(anchored.leaf_count, 4);
+
+    let proof = vec![&env, nodes[3].clone(), left.clone()];
+    assert!(client.verify_inclusion(&root_id, &leaves[2], &proof));
+
+    let outsider = env.crypto().sha256(&Bytes::from_array(&env, &[9; 8])).to_bytes();
+    assert!(!client.verify_inclusion(&root_id, &outsider, &proof));
+
+    let wrong_proof = vec![&env, nodes[0].clone(), right.clone()];
+    assert!(!client.verify_inclusion(&root_id, &leaves[2], &wrong_proof));
+
+    // An internal node passed off as a leaf, with a proof that is too short
+    assert!(!client.verify_inclusion(&root_id, &left, &vec![&env, right.clone()]));
+
+    // The 64-byte "document" made of two child nodes hashes to a value that
+    // is not the parent node, so it is not included at full depth either
+    let mut forged = Bytes::new(&env);
+    forged.append(&nodes[0].clone().into());
+    forged.append(&nodes[1].clone().into());
+    let forged_hash = env.crypto().sha256(&forged).to_bytes();
+    assert!(!client.verify_inclusion(&root_id, &forged_hash, &vec![&env, right.clone()]));
+    assert!(!client.verify_inclusion(&root_id, &forged_hash, &proof));
+}
+
+#[test]
+fn test_anchored_certificate_revocation() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+    let leaves: std::vec::Vec<BytesN<32>> = (0u8..2)
+        .map(|i| env.crypto().sha256(&Bytes::from_array(&env, &[i; 8])).to_bytes())
+        .collect();
+    let nodes: std::vec::Vec<BytesN<32>> = leaves.iter().map(|leaf| hash_leaf(&env, leaf)).collect();
+    let root = hash_pair(&env, &nodes[0], &nodes[1]);
+
+    env.mock_all_auths();
+    let root_id = client.anchor_batch_root(&institution, &5u64, &root, &2u32);
+    assert!(client.get_anchored_root(&root_id).status);
+
+    // Revoking one leaf leaves the rest of the batch valid
+    client.revoke_anchored_leaf(&root_id, &leaves[0]);
+    assert_eq!(env.auths().first().unwrap().0, institution);
+    assert!(!client.verify_inclusion(&root_id, &leaves[0], &vec![&env, nodes[1].clone()]));
+    assert!(client.verify_inclusion(&root_id, &leaves[1], &vec![&env, nodes[0].clone()]));
+
+    client.revoke_batch_root(&root_id);
+    assert_eq!(env.auths().first().unwrap().0, institution);
+    assert!(!client.get_anchored_root(&root_id).status);
+    assert!(!client.verify_inclusion(&root_id, &leaves[1], &vec![&env, nodes[0].clone()]));
+}
+
+#[test]
+fn test_merkle_proof_length_follows_leaf_count() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+    let leaves: std::vec::Vec<BytesN<32>> = (0u8..3)
+        .map(|i| env.crypto().sha256(&Bytes::from_array(&env, &[i; 8])).to_bytes())
+        .collect();
+    let nodes: std::vec::Vec<BytesN<32>> = leaves.iter().map(|leaf| hash_leaf(&env, leaf)).collect();
+
+    // The unpaired third leaf is hashed with itself
+    let left = hash_pair(&env, &nodes[0], &nodes[1]);
+    let right = hash_pair(&env, &nodes[2], &nodes[2]);
+    let root = hash_pair(&env, &left, &right);
+
+    env.mock_all_auths();
+    let root_id = client.anchor_batch_root(&institution, &5u64, &root, &3u32);
+
+    let proof = vec![&env, nodes[2].clone(), left.clone()];
+    assert!(client.verify_inclusion(&root_id, &leaves[2], &proof));
+    assert!(client.verify_inclusion(&root_id, &leaves[0], &vec![&env, nodes[1].clone(), right]));
+
+    // A single-leaf batch has the leaf node as its root
+    let single_root = client.anchor_batch_root(&institution, &6u64, &nodes[0], &1u32);
+    assert!(client.verify_inclusion(&single_root, &leaves[0], &Vec::new(&env)));
+    assert!(!client.verify_inclusion(&single_root, &leaves[0], &vec![&env, nodes[1].clone()]));
+
+    let result = client.try_anchor_batch_root(&institution, &7u64, &root, &0u32);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_private_certificate() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    let metadata = Bytes::from_slice(&env, b"Grade: A");
+    let salt = BytesN::from_array(&env, &[3; 32]);
+    let commitment = compute_commitment(&env, &salt, &student, &metadata);
+
+    env.mock_all_auths();
+    let cert_id = client.issue_private_certificate(&institution, &1u64, &commitment);
+
+    let certificate = client.get_private_certificate(&cert_id);
+    assert_eq!(certificate.commitment, commitment);
+    assert_eq!(certificate.institution, institution);
+    assert_eq!(client.list_certificates(&student).len(), 0);
+
+    assert!(client.verify_private_certificate(&cert_id, &student, &metadata, &salt));
+
+    let wrong_salt = BytesN::from_array(&env, &[4; 32]);
+    assert!(!client.verify_private_certificate(&cert_id, &student, &metadata, &wrong_salt));
+
+    let impostor = Address::generate(&env);
+    assert!(!client.verify_private_certificate(&cert_id, &impostor, &metadata, &salt));
+
+    let forged = Bytes::from_slice(&env, b"Grade: A+");
+    assert!(!client.verify_private_certificate(&cert_id, &student, &forged, &salt));
+
+    client.revoke_certificate(&cert_id);
+    assert!(!client.get_private_certificate(&cert_id).status);
+    assert!(!client.verify_private_certificate(&cert_id, &student, &metadata, &salt));
+}
+
+#[test]
+fn test_selective_disclosure() {
+    let env = Env::default();
+    let contract_id = env.register(CertificateContract, ());
+    let client = CertificateContractClient::new(&env, &contract_id);
+
+    let institution = Address::generate(&env);
+    let names = [
+        Symbol::new(&env, "course"),
+        Symbol::new(&env, "grade"),
+        Symbol::new(&env, "issued"),
+        Symbol::new(&env, "student"),
+    ];
+    let values = [
+        Bytes::from_slice(&env, b"Rust 101"),
+        Bytes::from_slice(&env, b"A"),
+        Bytes::from_slice(&env, b"2024-06-01"),
+        Bytes::from_slice(&env, b"Jane Doe"),
+    ];
+    let salts: std::vec::Vec<BytesN<32>> =
+        (0u8..4).map(|i| BytesN::from_array(&env, &[i; 32])).collect();
+    let leaves: std::vec::Vec<BytesN<32>> = (0..4)
+        .map(|i| hash_leaf(&env, &compute_field_leaf(&env, &names[i], &values[i], &salts[i])))
+        .collect();
+    let left = hash_pair(&env, &leaves[0], &leaves[1]);
+    let right = hash_pair(&env, &leaves[2], &leaves[3]);
+    let root = hash_pair(&env, &left, &right);
+
+    env.mock_all_auths();
+    let cert_id = client.issue_disclosable_certificate(&institution, &root, &4u32);
+    assert_eq!(client.get_disclosable_certificate(&cert_id).fields_root, root);
+
+    // Disclose only the course
+    let course = DisclosedField {
+        name: names[0].clone(),
+        value: values[0].clone(),
+        salt: salts[0].clone(),
+        proof: vec![&env, leaves[1].clone(), right.clone()],
+    };
+    assert!(client.verify_disclosed_fields(&cert_id, &vec![&env, course.clone()]));
+
+    // Disclose the course and the student together
+    let student = DisclosedField {
+        name: names[3].clone(),
+        value: values[3].clone(),
+        salt: salts[3].clone(),
+        proof: vec![&env, leaves[2].clone(), left.clone()],
+    };
+    assert!(client.verify_disclosed_fields(&cert_id, &vec![&env, course.clone(), student]));
+
+    // A forged value fails even alongside a genuine field
+    let forged_grade = DisclosedField {
+        name: names[1].clone(),
+        value: Bytes::from_slice(&env, b"A+"),
+        salt: salts[1].clone(),
+        proof: vec![&env, leaves[0].clone(), right.clone()],
+    };
+    assert!(!client.verify_disclosed_fields(&cert_id, &vec![&env, course.clone(), forged_grade]));
+
+    assert!(!client.verify_disclosed_fields(&cert_id, &Vec::new(&env)));
+
+    // Proofs must be exactly as long as the tree over `field_count` leaves
+    let mut padded = course.clone();
+    padded.proof.push_back(root.clone());
+    assert!(!client.verify_disclosed_fields(&cert_id, &vec![&env, padded]));
+
+    client.revoke_certificate(&cert_id);
+    assert!(!client.get_disclosable_certificate(&cert_id).status);
+    assert!(!client.verify_disclosed_fields(&cert_id, &vec![&env, course]));
+}
@@ -1,9 +1,11 @@
 #![no_std] 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, panic_with_error, 
-    Address, Bytes, Env, Symbol, Vec, Val, IntoVal, Map, TryFromVal
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token,
+    Address, Bytes, BytesN, Env, Symbol, Vec, Val, IntoVal, Map, TryFromVal
 };
 
+const MAX_FEE_BPS: u32 = 10_000;
+
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -14,6 +16,66 @@ pub enum Error {
     InsufficientFunds = 4,
     StudentAlreadyEnrolled = 5,
     StudentsEnrolled = 6,
+    AdminNotSet = 7,
+    AdminAlreadySet = 8,
+    InvalidFee = 9,
+    FeeConfigNotSet = 10,
+    StudentNotEnrolled = 11,
+    EnrollmentCompleted = 12,
+    InvalidStatusTransition = 13,
+    CourseNotOpen = 14,
+    CourseArchived = 15,
+    VersionNotFound = 16,
+    CourseFull = 17,
+    CourseNotFull = 18,
+    AlreadyWaitlisted = 19,
+    NotWaitlisted = 20,
+    InvalidSchedule = 21,
+    EnrollmentNotOpen = 22,
+    EnrollmentClosed = 23,
+    CourseNotStarted = 24,
+    CourseEnded = 25,
+    PrerequisitesNotMet = 26,
+    InvalidMilestones = 27,
+    MilestoneNotFound = 28,
+    MilestonesIncomplete = 29,
+    InvalidScore = 30,
+    BelowPassingScore = 31,
+    CertificateIssuanceFailed = 32,
+    FeeTokenLocked = 33,
+    BatchTooLarge = 34,
+}
+
+/// Upper bound on students completed by one `complete_course_batch` call.
+/// Each student costs a certificate and several storage writes, so larger
+/// cohorts are completed in several chunks to stay within the ledger's
+/// per-transaction resource limits.
+pub const MAX_COMPLETION_BATCH: u32 = 50;
+
+/// Score scale used for grading, scores range from 0 to `MAX_SCORE`.
+pub const MAX_SCORE: u32 = 100;
+
+/// Honours awarded on top of a passing grade. Mirrors the `Honours` type of
+/// the certificate contract.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Honours {
+    None,
+    Merit,
+    Distinction,
+    HighDistinction,
+}
+
+/// Lifecycle of a course. New courses start out `Published`; `Closed`
+/// stops new enrollments and `Archived` freezes the course for good while
+/// keeping it and its enrollments readable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CourseStatus {
+    Draft,
+    Published,
+    Closed,
+    Archived,
 }
 
 #[derive(Clone)]
@@ -26,6 +88,71 @@ pub struct Course {
     pub metadata: Bytes,
     pub certificate_id: u64,
     pub created_at: u64,
+    pub status: CourseStatus,
+    pub version: u32,
+    pub updated_at: u64,
+    pub max_students: Option<u32>,
+    pub schedule: CourseSchedule,
+    pub prerequisites: Vec<u64>,
+    pub milestones: Vec<Milestone>,
+    pub passing_score: u32,
+}
+
+/// A module or checkpoint of a course. Every `required` milestone has to be
+/// recorded for a student before the course can be completed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Milestone {
+    pub id: u32,
+    pub title: Bytes,
+    pub required: bool,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Progress {
+    pub course_id: u64,
+    pub student: Address,
+    pub completed_milestones: Vec<u32>,
+    pub required_completed: u32,
+    pub required_total: u32,
+    pub completed: bool,
+}
+
+/// Enrollment window and teaching period of a course, as ledger
+/// timestamps. Unset bounds are not enforced.
+#[derive(Clone)]
+#[contracttype]
+pub struct CourseSchedule {
+    pub enrollment_opens_at: Option<u64>,
+    pub enrollment_closes_at: Option<u64>,
+    pub starts_at: Option<u64>,
+    pub ends_at: Option<u64>,
+}
+
+/// A student queued for a seat in a full course. When a payment token is
+/// configured the course price is held by the contract in `deposit` until
+/// the student is promoted or leaves the waitlist.
+#[derive(Clone)]
+#[contracttype]
+pub struct WaitlistEntry {
+    pub student: Address,
+    pub joined_at: u64,
+    pub token: Option<Address>,
+    pub deposit: i128,
+}
+
+/// Snapshot of the editable fields of a course at a given version.
+/// `replaced_at` is `None` for the version that is currently live.
+#[derive(Clone)]
+#[contracttype]
+pub struct CourseVersion {
+    pub version: u32,
+    pub title: Bytes,
+    pub price: u64,
+    pub metadata: Bytes,
+    pub effective_from: u64,
+    pub replaced_at: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -35,6 +162,54 @@ pub struct Enrollment {
     pub course_id: u64,
     pub enrolled_at: u64,
     pub completed: bool,
+    pub completed_version: Option<u32>,
+    pub completed_milestones: Vec<u32>,
+    pub score: Option<u32>,
+    pub honours: Honours,
+}
+
+/// Platform fee settings applied to every paid enrollment.
+///
+/// `fee_bps` is expressed in basis points (1/100th of a percent) of the
+/// course price; the remainder is paid straight to the institution.
+#[derive(Clone)]
+#[contracttype]
+pub struct FeeConfig {
+    pub token: Address,
+    pub treasury: Address,
+    pub fee_bps: u32,
+}
+
+/// One student's result in a `complete_course_batch` call.
+#[derive(Clone)]
+#[contracttype]
+pub struct CompletionRequest {
+    pub student: Address,
+    pub score: u32,
+    pub honours: Honours,
+    pub content_hash: BytesN<32>,
+}
+
+/// Per-student input to the certificate contract's
+/// `issue_certificates_batch`. Mirrors its `CertificateRequest` type.
+#[derive(Clone)]
+#[contracttype]
+pub struct CertificateRequest {
+    pub student: Address,
+    pub metadata: Bytes,
+    pub score: u32,
+    pub honours: Honours,
+    pub content_hash: BytesN<32>,
+}
+
+/// Outcome of a batch completion for one student. Exactly one of
+/// `certificate_id` and `error` is set; `error` holds an `Error` code.
+#[derive(Clone)]
+#[contracttype]
+pub struct CompletionResult {
+    pub student: Address,
+    pub certificate_id: Option<u64>,
+    pub error: Option<u32>,
 }
 
 #[contract]
@@ -51,18 +226,27 @@ impl<'a> CertificateClient<'a> {
         Self { env, contract_id }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn issue_certificate(
         &self,
         student: Address,
         course_id: u64,
         institution: Address,
         metadata: Bytes,
+        course_version: u32,
+        score: u32,
+        honours: Honours,
+        content_hash: BytesN<32>,
     ) -> u64 {
         let args = (
             student,
             course_id,
             institution.clone(),
-            metadata
+            metadata,
+            course_version,
+            score,
+            honours,
+            content_hash,
         );
 
         self.env
@@ -72,6 +256,58 @@ impl<'a> CertificateClient<'a> {
                 args.into_val(self.env),
             )
     }
+
+    pub fn issue_certificates_batch(
+        &self,
+        institution: Address,
+        course_id: u64,
+        course_version: u32,
+        requests: Vec<CertificateRequest>,
+    ) -> Vec<u64> {
+        let args = (institution, course_id, course_version, requests);
+
+        self.env
+            .invoke_contract::<Vec<u64>>(
+                &self.contract_id,
+                &Symbol::new(self.env, "issue_certificates_batch"),
+                args.into_val(self.env),
+            )
+    }
+
+    // Same call as `issue_certificate` but returns `None` instead of
+    // aborting the transaction when the certificate contract fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_issue_certificate(
+        &self,
+        student: Address,
+        course_id: u64,
+        institution: Address,
+        metadata: Bytes,
+        course_version: u32,
+        score: u32,
+        honours: Honours,
+        content_hash: BytesN<32>,
+    ) -> Option<u64> {
+        let args = (
+            student,
+            course_id,
+            institution,
+            metadata,
+            course_version,
+            score,
+            honours,
+            content_hash,
+        );
+
+        match self.env.try_invoke_contract::<u64, soroban_sdk::Error>(
+            &self.contract_id,
+            &Symbol::new(self.env, "issue_certificate"),
+            args.into_val(self.env),
+        ) {
+            Ok(Ok(certificate_id)) => Some(certificate_id),
+            _ => None,
+        }
+    }
 }
 
 pub struct InstitutionClient<'a> {
@@ -110,6 +346,7 @@ impl<'a> InstitutionClient<'a> {
     
 #[contractimpl]
 impl CourseContract {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_course(
         env: Env,
         title: Bytes,
@@ -118,6 +355,7 @@ impl CourseContract {
         metadata: Bytes,
         certificate_id: u64,
         institution_contract_id: Address,
+        prerequisites: Vec<u64>,
     ) -> u64 {
         institution.require_auth();
 
@@ -127,7 +365,17 @@ impl CourseContract {
             panic_with_error!(&env, Error::InstitutionNotVerified);
         }
 
-        let id: u64 = env.ledger().sequence().into();
+        for prerequisite_id in prerequisites.iter() {
+            if !env.storage().persistent().has(&prerequisite_id) {
+                panic_with_error!(&env, Error::CourseNotFound);
+            }
+        }
+
+        // Ledger sequence numbers repeat within a ledger, so ids come from a
+        // counter to keep courses created in the same ledger apart.
+        let next_id_key = Bytes::from_slice(&env, b"next_course_id");
+        let id: u64 = env.storage().persistent().get(&next_id_key).unwrap_or(1);
+        env.storage().persistent().set(&next_id_key, &(id + 1));
         let created_at = env.ledger().timestamp();
 
         let course = Course {
@@ -138,6 +386,19 @@ impl CourseContract {
             metadata,
             certificate_id,
             created_at,
+            status: CourseStatus::Published,
+            version: 1,
+            updated_at: created_at,
+            max_students: None,
+            schedule: CourseSchedule {
+                enrollment_opens_at: None,
+                enrollment_closes_at: None,
+                starts_at: None,
+                ends_at: None,
+            },
+            prerequisites,
+            milestones: Vec::new(&env),
+            passing_score: 0,
         };
 
         env.storage().persistent().set(&id, &course);
@@ -152,160 +413,1334 @@ impl CourseContract {
         course_ids.push_back(id);
         env.storage().persistent().set(&courses_key, &course_ids);
 
+        Self::add_to_index(&env, &Self::get_institution_courses_key(&env, &institution), id);
+        Self::add_to_index(&env, &Self::get_status_courses_key(&env, CourseStatus::Published), id);
+        Self::add_to_price_index(&env, price, id);
+
         id
     }
 
     pub fn enroll_in_course(env: Env, course_id: u64, student: Address) {
         student.require_auth();
         
-        let _course: Course = env
+        let course: Course = env
             .storage()
             .persistent()
             .get(&course_id)
             .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.status != CourseStatus::Published {
+            panic_with_error!(&env, Error::CourseNotOpen);
+        }
+
+        Self::check_enrollment_window(&env, &course);
+        Self::check_prerequisites(&env, &course, &student);
             
         let enrollment_key = Self::get_enrollment_key(&env, &course_id, &student);
         if env.storage().persistent().has(&enrollment_key) {
             panic_with_error!(&env, Error::StudentAlreadyEnrolled);
         }
-        
-        
-        let enrollment = Enrollment {
-            student: student.clone(),
-            course_id,
-            enrolled_at: env.ledger().timestamp(),
-            completed: false,
-        };
-        
-        env.storage().persistent().set(&enrollment_key, &enrollment);
-        
-        let student_courses_key = Self::get_student_courses_key(&env, &student);
-        let mut student_courses = env
-            .storage()
-            .persistent()
-            .get::<Bytes, Vec<u64>>(&student_courses_key)
-            .unwrap_or_else(|| Vec::new(&env));
-            
-        student_courses.push_back(course_id);
-        env.storage().persistent().set(&student_courses_key, &student_courses);
-        
-        let course_students_key = Self::get_course_students_key(&env, &course_id);
-        let mut course_students = env
-            .storage()
-            .persistent()
-            .get::<Bytes, Vec<Address>>(&course_students_key)
-            .unwrap_or_else(|| Vec::new(&env));
-            
-        course_students.push_back(student.clone());
-        env.storage().persistent().set(&course_students_key, &course_students);
+
+        if Self::is_full(&env, &course) {
+            panic_with_error!(&env, Error::CourseFull);
+        }
+
+        Self::collect_payment(&env, &student, &course);
+        Self::add_enrollment(&env, course_id, &student);
     }
-    
-    pub fn complete_course(
-        env: Env, 
-        course_id: u64, 
-        student: Address, 
-        certificate_contract_id: Address
-    ) {
+
+    /// Queues a student for a full course. Students are promoted in the
+    /// order they joined whenever a seat frees up.
+    pub fn join_waitlist(env: Env, course_id: u64, student: Address) {
+        student.require_auth();
+
         let course: Course = env
             .storage()
             .persistent()
             .get(&course_id)
             .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
-            
-        course.institution.require_auth();
-        
+
+        if course.status != CourseStatus::Published {
+            panic_with_error!(&env, Error::CourseNotOpen);
+        }
+
+        Self::check_enrollment_window(&env, &course);
+        Self::check_prerequisites(&env, &course, &student);
+
         let enrollment_key = Self::get_enrollment_key(&env, &course_id, &student);
-        let mut enrollment: Enrollment = env
+        if env.storage().persistent().has(&enrollment_key) {
+            panic_with_error!(&env, Error::StudentAlreadyEnrolled);
+        }
+
+        if !Self::is_full(&env, &course) {
+            panic_with_error!(&env, Error::CourseNotFull);
+        }
+
+        let waitlist_key = Self::get_waitlist_key(&env, &course_id);
+        let mut waitlist = env
             .storage()
             .persistent()
-            .get(&enrollment_key)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::Unauthorized));
-            
-        enrollment.completed = true;
-        env.storage().persistent().set(&enrollment_key, &enrollment);
-        
-        let certificate_client = CertificateClient::new(&env, certificate_contract_id);
-        certificate_client.issue_certificate(
+            .get::<Bytes, Vec<WaitlistEntry>>(&waitlist_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        if waitlist.iter().any(|entry| entry.student == student) {
+            panic_with_error!(&env, Error::AlreadyWaitlisted);
+        }
+
+        let (token, deposit) = Self::escrow_payment(&env, &student, &course);
+
+        waitlist.push_back(WaitlistEntry {
             student,
-            course_id,
-            course.institution,
-            course.metadata
-        );
-    }
-    
-    pub fn get_course(env: Env, id: u64) -> Course {
-        env.storage()
-            .persistent()
-            .get(&id)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound))
+            joined_at: env.ledger().timestamp(),
+            token,
+            deposit,
+        });
+        env.storage().persistent().set(&waitlist_key, &waitlist);
     }
-    
-    pub fn list_courses(env: Env) -> Vec<Course> {
-        let mut courses = Vec::new(&env);
-        
-        let courses_key = Bytes::from_slice(&env, b"courses");
-        let course_ids = env
+
+    /// Removes a student from the waitlist and refunds any held deposit.
+    pub fn leave_waitlist(env: Env, course_id: u64, student: Address) {
+        student.require_auth();
+
+        let waitlist_key = Self::get_waitlist_key(&env, &course_id);
+        let waitlist = env
             .storage()
             .persistent()
-            .get::<Bytes, Vec<u64>>(&courses_key)
+            .get::<Bytes, Vec<WaitlistEntry>>(&waitlist_key)
             .unwrap_or_else(|| Vec::new(&env));
-            
-        for i in 0..course_ids.len() {
-            let course_id = course_ids.get(i).unwrap();
-            if let Some(course) = env.storage().persistent().get::<u64, Course>(&course_id) {
-                courses.push_back(course);
+
+        let mut new_waitlist = Vec::new(&env);
+        let mut removed: Option<WaitlistEntry> = None;
+        for entry in waitlist.iter() {
+            if entry.student == student && removed.is_none() {
+                removed = Some(entry);
+            } else {
+                new_waitlist.push_back(entry);
+            }
+        }
+
+        let entry = removed.unwrap_or_else(|| panic_with_error!(&env, Error::NotWaitlisted));
+        env.storage().persistent().set(&waitlist_key, &new_waitlist);
+
+        if let Some(token) = entry.token {
+            if entry.deposit > 0 {
+                Self::adjust_escrowed_deposits(&env, -entry.deposit);
+                token::Client::new(&env, &token).transfer(
+                    &env.current_contract_address(),
+                    &entry.student,
+                    &entry.deposit,
+                );
             }
         }
-        
-        courses
     }
-    
-    pub fn remove_course(env: Env, id: u64, institution: Address) {
+
+    pub fn get_waitlist(env: Env, course_id: u64) -> Vec<WaitlistEntry> {
+        env.storage()
+            .persistent()
+            .get::<Bytes, Vec<WaitlistEntry>>(&Self::get_waitlist_key(&env, &course_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Sets or clears the seat limit of a course. Only students who have not
+    /// completed the course hold a seat. Raising the limit promotes
+    /// waitlisted students into the new seats.
+    pub fn set_max_students(
+        env: Env,
+        course_id: u64,
+        institution: Address,
+        max_students: Option<u32>,
+    ) {
         institution.require_auth();
-        
-        let course: Course = env
+
+        let mut course: Course = env
             .storage()
             .persistent()
-            .get(&id)
+            .get(&course_id)
             .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
-            
+
         if course.institution != institution {
             panic_with_error!(&env, Error::Unauthorized);
         }
-        
-        let course_students_key = Self::get_course_students_key(&env, &id);
-        let course_students = env
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        course.max_students = max_students;
+        env.storage().persistent().set(&course_id, &course);
+
+        Self::promote_from_waitlist(&env, &course);
+    }
+    
+    pub fn unenroll(env: Env, course_id: u64, student: Address) {
+        student.require_auth();
+
+        let course: Course = env
             .storage()
             .persistent()
-            .get::<Bytes, Vec<Address>>(&course_students_key)
-            .unwrap_or_else(|| Vec::new(&env));
-            
-        if !course_students.is_empty() {
-            panic_with_error!(&env, Error::StudentsEnrolled);
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
         }
-        
-        env.storage().persistent().remove(&id);
-        
-        let courses_key = Bytes::from_slice(&env, b"courses");
-        let course_ids = env
+
+        Self::remove_enrollment(&env, course_id, &student);
+    }
+
+    pub fn withdraw_student(env: Env, course_id: u64, student: Address, institution: Address) {
+        institution.require_auth();
+
+        let course: Course = env
             .storage()
             .persistent()
-            .get::<Bytes, Vec<u64>>(&courses_key)
-            .unwrap_or_else(|| Vec::new(&env));
-            
-        let mut new_course_ids = Vec::new(&env);
-        for i in 0..course_ids.len() {
-            let course_id = course_ids.get(i).unwrap();
-            if course_id != id {
-                new_course_ids.push_back(course_id);
-            }
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
         }
-        
-        env.storage().persistent().set(&courses_key, &new_course_ids);
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        Self::remove_enrollment(&env, course_id, &student);
     }
     
-    fn get_enrollment_key(env: &Env, course_id: &u64, student: &Address) -> Bytes {
-        let mut key = Bytes::new(env);
+    /// Completes a student's enrollment and issues their certificate.
+    /// `content_hash` is the SHA-256 of the certificate document handed to
+    /// the student and is anchored on the issued certificate.
+    #[allow(clippy::too_many_arguments)]
+    pub fn complete_course(
+        env: Env, 
+        course_id: u64, 
+        student: Address, 
+        certificate_contract_id: Address,
+        score: u32,
+        honours: Honours,
+        content_hash: BytesN<32>,
+    ) {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+            
+        course.institution.require_auth();
+
+        let enrollment = Self::check_completion(&env, &course, &student, score)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+        
+        let certificate_client = CertificateClient::new(&env, certificate_contract_id);
+        certificate_client.issue_certificate(
+            student,
+            course_id,
+            course.institution.clone(),
+            course.metadata.clone(),
+            course.version,
+            score,
+            honours,
+            content_hash,
+        );
+
+        Self::mark_completed(&env, &course, enrollment, score, honours);
+        Self::promote_from_waitlist(&env, &course);
+    }
+
+    /// Completes and certifies a cohort in one call. With `atomic` set the
+    /// first failing student aborts the whole batch and the certificates
+    /// are issued in a single `issue_certificates_batch` call; otherwise
+    /// failures are reported per student and the rest of the cohort goes
+    /// through. At most `MAX_COMPLETION_BATCH` students are accepted per
+    /// call, so larger cohorts are completed in chunks.
+    pub fn complete_course_batch(
+        env: Env,
+        course_id: u64,
+        completions: Vec<CompletionRequest>,
+        certificate_contract_id: Address,
+        atomic: bool,
+    ) -> Vec<CompletionResult> {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        course.institution.require_auth();
+
+        if completions.len() > MAX_COMPLETION_BATCH {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+
+        let certificate_client = CertificateClient::new(&env, certificate_contract_id);
+        let results = if atomic {
+            Self::complete_all(&env, &course, &certificate_client, &completions)
+        } else {
+            let mut results = Vec::new(&env);
+            for completion in completions.iter() {
+                let issued = Self::complete_one(&env, &course, &certificate_client, &completion);
+                let result = match issued {
+                    Ok(certificate_id) => CompletionResult {
+                        student: completion.student,
+                        certificate_id: Some(certificate_id),
+                        error: None,
+                    },
+                    Err(error) => CompletionResult {
+                        student: completion.student,
+                        certificate_id: None,
+                        error: Some(error as u32),
+                    },
+                };
+                results.push_back(result);
+            }
+            results
+        };
+
+        Self::promote_from_waitlist(&env, &course);
+
+        results
+    }
+    
+    /// Replaces the title, price and metadata of a course, keeping the
+    /// previous values as a numbered version. Returns the new version.
+    pub fn update_course(
+        env: Env,
+        course_id: u64,
+        institution: Address,
+        title: Bytes,
+        price: u64,
+        metadata: Bytes,
+    ) -> u32 {
+        institution.require_auth();
+
+        let mut course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        let now = env.ledger().timestamp();
+
+        let previous = CourseVersion {
+            version: course.version,
+            title: course.title.clone(),
+            price: course.price,
+            metadata: course.metadata.clone(),
+            effective_from: course.updated_at,
+            replaced_at: Some(now),
+        };
+        let version_key = Self::get_course_version_key(&env, &course_id, course.version);
+        env.storage().persistent().set(&version_key, &previous);
+
+        if course.price != price {
+            Self::remove_from_price_index(&env, course.price, course_id);
+            Self::add_to_price_index(&env, price, course_id);
+        }
+
+        course.title = title;
+        course.price = price;
+        course.metadata = metadata;
+        course.version += 1;
+        course.updated_at = now;
+        env.storage().persistent().set(&course_id, &course);
+
+        course.version
+    }
+
+    pub fn get_course_version(env: Env, course_id: u64, version: u32) -> CourseVersion {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if version == course.version {
+            return CourseVersion {
+                version,
+                title: course.title,
+                price: course.price,
+                metadata: course.metadata,
+                effective_from: course.updated_at,
+                replaced_at: None,
+            };
+        }
+
+        env.storage()
+            .persistent()
+            .get(&Self::get_course_version_key(&env, &course_id, version))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::VersionNotFound))
+    }
+
+    pub fn list_course_versions(env: Env, course_id: u64) -> Vec<CourseVersion> {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        let mut versions = Vec::new(&env);
+        for version in 1..=course.version {
+            versions.push_back(Self::get_course_version(env.clone(), course_id, version));
+        }
+
+        versions
+    }
+
+    pub fn set_course_schedule(
+        env: Env,
+        course_id: u64,
+        institution: Address,
+        schedule: CourseSchedule,
+    ) {
+        institution.require_auth();
+
+        let mut course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        if let (Some(opens_at), Some(closes_at)) =
+            (schedule.enrollment_opens_at, schedule.enrollment_closes_at)
+        {
+            if opens_at > closes_at {
+                panic_with_error!(&env, Error::InvalidSchedule);
+            }
+        }
+        if let (Some(starts_at), Some(ends_at)) = (schedule.starts_at, schedule.ends_at) {
+            if starts_at > ends_at {
+                panic_with_error!(&env, Error::InvalidSchedule);
+            }
+        }
+
+        course.schedule = schedule;
+        env.storage().persistent().set(&course_id, &course);
+    }
+
+    /// Moves a course through its lifecycle. Allowed transitions are
+    /// `Draft -> Published`, `Published -> Closed`, `Closed -> Published`,
+    /// `Published -> Draft` while nobody is enrolled, and any non-archived
+    /// state to `Archived`.
+    pub fn set_course_status(env: Env, course_id: u64, institution: Address, status: CourseStatus) {
+        institution.require_auth();
+
+        let mut course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let allowed = match (course.status, status) {
+            (CourseStatus::Draft, CourseStatus::Published) => true,
+            (CourseStatus::Published, CourseStatus::Closed) => true,
+            (CourseStatus::Closed, CourseStatus::Published) => true,
+            (CourseStatus::Published, CourseStatus::Draft) => {
+                let course_students_key = Self::get_course_students_key(&env, &course_id);
+                env.storage()
+                    .persistent()
+                    .get::<Bytes, Vec<Address>>(&course_students_key)
+                    .is_none_or(|students| students.is_empty())
+            }
+            (CourseStatus::Archived, _) => false,
+            (_, CourseStatus::Archived) => true,
+            _ => false,
+        };
+
+        if !allowed {
+            panic_with_error!(&env, Error::InvalidStatusTransition);
+        }
+
+        let old_status_key = Self::get_status_courses_key(&env, course.status);
+        Self::remove_from_index(&env, &old_status_key, course_id);
+        Self::add_to_index(&env, &Self::get_status_courses_key(&env, status), course_id);
+
+        course.status = status;
+        env.storage().persistent().set(&course_id, &course);
+    }
+    
+    /// Replaces the milestones of a course. Milestone ids must be unique.
+    pub fn set_course_milestones(
+        env: Env,
+        course_id: u64,
+        institution: Address,
+        milestones: Vec<Milestone>,
+    ) {
+        institution.require_auth();
+
+        let mut course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        for (i, milestone) in milestones.iter().enumerate() {
+            if milestones
+                .iter()
+                .skip(i + 1)
+                .any(|other| other.id == milestone.id)
+            {
+                panic_with_error!(&env, Error::InvalidMilestones);
+            }
+        }
+
+        course.milestones = milestones;
+        env.storage().persistent().set(&course_id, &course);
+    }
+
+    /// Sets the minimum score, out of `MAX_SCORE`, a student needs for the
+    /// course to be completed.
+    pub fn set_passing_score(env: Env, course_id: u64, institution: Address, passing_score: u32) {
+        institution.require_auth();
+
+        let mut course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        if passing_score > MAX_SCORE {
+            panic_with_error!(&env, Error::InvalidScore);
+        }
+
+        course.passing_score = passing_score;
+        env.storage().persistent().set(&course_id, &course);
+    }
+
+    /// Marks a milestone as reached by an enrolled student. Recording the
+    /// same milestone twice has no effect.
+    pub fn record_progress(env: Env, course_id: u64, student: Address, milestone_id: u32) {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        course.institution.require_auth();
+
+        if course.status == CourseStatus::Archived {
+            panic_with_error!(&env, Error::CourseArchived);
+        }
+
+        if !course.milestones.iter().any(|milestone| milestone.id == milestone_id) {
+            panic_with_error!(&env, Error::MilestoneNotFound);
+        }
+
+        let enrollment_key = Self::get_enrollment_key(&env, &course_id, &student);
+        let mut enrollment: Enrollment = env
+            .storage()
+            .persistent()
+            .get(&enrollment_key)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::StudentNotEnrolled));
+
+        if enrollment.completed {
+            panic_with_error!(&env, Error::EnrollmentCompleted);
+        }
+
+        if !enrollment.completed_milestones.contains(milestone_id) {
+            enrollment.completed_milestones.push_back(milestone_id);
+            env.storage().persistent().set(&enrollment_key, &enrollment);
+        }
+    }
+
+    pub fn get_progress(env: Env, course_id: u64, student: Address) -> Progress {
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&course_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+
+        let enrollment: Enrollment = env
+            .storage()
+            .persistent()
+            .get(&Self::get_enrollment_key(&env, &course_id, &student))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::StudentNotEnrolled));
+
+        Progress {
+            course_id,
+            student,
+            required_completed: Self::count_required_completed(&course, &enrollment),
+            required_total: Self::count_required(&course),
+            completed_milestones: enrollment.completed_milestones,
+            completed: enrollment.completed,
+        }
+    }
+    
+    pub fn get_enrollment(env: Env, course_id: u64, student: Address) -> Enrollment {
+        env.storage()
+            .persistent()
+            .get(&Self::get_enrollment_key(&env, &course_id, &student))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::StudentNotEnrolled))
+    }
+
+    /// Returns up to `limit` of the student's enrollments, skipping the
+    /// first `start`, in the order the student enrolled.
+    pub fn list_student_enrollments(
+        env: Env,
+        student: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Enrollment> {
+        let course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(&Self::get_student_courses_key(&env, &student))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut enrollments = Vec::new(&env);
+        let end = course_ids.len().min(start.saturating_add(limit));
+        for i in start..end {
+            let course_id = course_ids.get(i).unwrap();
+            let enrollment_key = Self::get_enrollment_key(&env, &course_id, &student);
+            if let Some(enrollment) = env
+                .storage()
+                .persistent()
+                .get::<Bytes, Enrollment>(&enrollment_key)
+            {
+                enrollments.push_back(enrollment);
+            }
+        }
+
+        enrollments
+    }
+
+    /// Returns up to `limit` students enrolled in a course, skipping the
+    /// first `start`, in enrollment order.
+    pub fn list_course_students(env: Env, course_id: u64, start: u32, limit: u32) -> Vec<Address> {
+        let course_students = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<Address>>(&Self::get_course_students_key(&env, &course_id))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let end = course_students.len().min(start.saturating_add(limit));
+        if start >= end {
+            return Vec::new(&env);
+        }
+
+        course_students.slice(start..end)
+    }
+    
+    pub fn get_course(env: Env, id: u64) -> Course {
+        env.storage()
+            .persistent()
+            .get(&id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound))
+    }
+    
+    pub fn list_courses(env: Env) -> Vec<Course> {
+        let mut courses = Vec::new(&env);
+        
+        let courses_key = Bytes::from_slice(&env, b"courses");
+        let course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(&courses_key)
+            .unwrap_or_else(|| Vec::new(&env));
+            
+        for i in 0..course_ids.len() {
+            let course_id = course_ids.get(i).unwrap();
+            if let Some(course) = env.storage().persistent().get::<u64, Course>(&course_id) {
+                courses.push_back(course);
+            }
+        }
+        
+        courses
+    }
+    
+    /// Lists published courses that currently accept enrollments: inside
+    /// their enrollment window and with seats left.
+    pub fn list_open_courses(env: Env) -> Vec<Course> {
+        let now = env.ledger().timestamp();
+        let mut courses = Vec::new(&env);
+
+        for course in Self::list_courses(env.clone()).iter() {
+            if course.status == CourseStatus::Published
+                && Self::enrollment_window_contains(&course, now)
+                && !Self::is_full(&env, &course)
+            {
+                courses.push_back(course);
+            }
+        }
+
+        courses
+    }
+    
+    pub fn remove_course(env: Env, id: u64, institution: Address) {
+        institution.require_auth();
+        
+        let course: Course = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CourseNotFound));
+            
+        if course.institution != institution {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+        
+        let course_students_key = Self::get_course_students_key(&env, &id);
+        let course_students = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<Address>>(&course_students_key)
+            .unwrap_or_else(|| Vec::new(&env));
+            
+        if !course_students.is_empty() {
+            panic_with_error!(&env, Error::StudentsEnrolled);
+        }
+        
+        env.storage().persistent().remove(&id);
+        
+        let courses_key = Bytes::from_slice(&env, b"courses");
+        let course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(&courses_key)
+            .unwrap_or_else(|| Vec::new(&env));
+            
+        let mut new_course_ids = Vec::new(&env);
+        for i in 0..course_ids.len() {
+            let course_id = course_ids.get(i).unwrap();
+            if course_id != id {
+                new_course_ids.push_back(course_id);
+            }
+        }
+        
+        env.storage().persistent().set(&courses_key, &new_course_ids);
+
+        Self::remove_from_index(&env, &Self::get_institution_courses_key(&env, &institution), id);
+        Self::remove_from_index(&env, &Self::get_status_courses_key(&env, course.status), id);
+        Self::remove_from_price_index(&env, course.price, id);
+    }
+
+    /// Lists the courses offered by an institution, paginated with
+    /// `start`/`limit` in creation order.
+    pub fn list_courses_by_institution(
+        env: Env,
+        institution: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Course> {
+        let key = Self::get_institution_courses_key(&env, &institution);
+        Self::load_course_page(&env, &key, start, limit)
+    }
+
+    /// Lists the courses currently in `status`, paginated with
+    /// `start`/`limit`.
+    pub fn list_courses_by_status(
+        env: Env,
+        status: CourseStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Course> {
+        let key = Self::get_status_courses_key(&env, status);
+        Self::load_course_page(&env, &key, start, limit)
+    }
+
+    /// Lists courses priced between `min_price` and `max_price` inclusive,
+    /// cheapest first. `start`/`limit` page through the matching courses.
+    pub fn list_courses_by_price(
+        env: Env,
+        min_price: u64,
+        max_price: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Course> {
+        let index = Self::get_price_index(&env);
+        let first = Self::price_index_position(&index, (min_price, 0));
+
+        let mut courses = Vec::new(&env);
+        let mut i = first.saturating_add(start);
+        while i < index.len() && courses.len() < limit {
+            let (price, course_id) = index.get_unchecked(i);
+            if price > max_price {
+                break;
+            }
+            if let Some(course) = env.storage().persistent().get::<u64, Course>(&course_id) {
+                courses.push_back(course);
+            }
+            i += 1;
+        }
+
+        courses
+    }
+    
+    pub fn set_admin(env: Env, admin: Address) {
+        let key = Bytes::from_slice(&env, b"admin");
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, Error::AdminAlreadySet);
+        }
+        env.storage().persistent().set(&key, &admin);
+    }
+
+    pub fn set_fee_config(
+        env: Env,
+        admin: Address,
+        token: Address,
+        treasury: Address,
+        fee_bps: u32,
+    ) {
+        Self::require_admin(&env, &admin);
+
+        if fee_bps > MAX_FEE_BPS {
+            panic_with_error!(&env, Error::InvalidFee);
+        }
+
+        // Fees and waitlist deposits are held in the configured token, so it
+        // cannot change until they have been paid out.
+        if let Some(current) = Self::get_fee_config(env.clone()) {
+            if current.token != token
+                && (Self::get_accumulated_fees(env.clone()) > 0
+                    || Self::get_escrowed_deposits(&env) > 0)
+            {
+                panic_with_error!(&env, Error::FeeTokenLocked);
+            }
+        }
+
+        let config = FeeConfig {
+            token,
+            treasury,
+            fee_bps,
+        };
+        env.storage()
+            .persistent()
+            .set(&Bytes::from_slice(&env, b"fee_config"), &config);
+    }
+
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage()
+            .persistent()
+            .get(&Bytes::from_slice(&env, b"fee_config"))
+    }
+
+    pub fn get_accumulated_fees(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Bytes::from_slice(&env, b"accumulated_fees"))
+            .unwrap_or(0)
+    }
+
+    /// Sends every fee collected so far to the configured treasury and
+    /// returns the amount transferred.
+    pub fn withdraw_fees(env: Env, admin: Address) -> i128 {
+        Self::require_admin(&env, &admin);
+
+        let config = Self::get_fee_config(env.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::FeeConfigNotSet));
+
+        let amount = Self::get_accumulated_fees(env.clone());
+        if amount > 0 {
+            token::Client::new(&env, &config.token).transfer(
+                &env.current_contract_address(),
+                &config.treasury,
+                &amount,
+            );
+            env.storage()
+                .persistent()
+                .set(&Bytes::from_slice(&env, b"accumulated_fees"), &0i128);
+        }
+
+        amount
+    }
+
+    fn require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Bytes::from_slice(env, b"admin"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::AdminNotSet));
+
+        if stored_admin != *admin {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+    }
+
+    // Charges the course price when a payment token has been configured. The
+    // institution's share goes straight to its address while the platform fee
+    // is held by this contract until the admin withdraws it.
+    fn collect_payment(env: &Env, student: &Address, course: &Course) {
+        let config = match Self::get_fee_config(env.clone()) {
+            Some(config) => config,
+            None => return,
+        };
+
+        let price = course.price as i128;
+        if price == 0 {
+            return;
+        }
+
+        if token::Client::new(env, &config.token).balance(student) < price {
+            panic_with_error!(env, Error::InsufficientFunds);
+        }
+
+        Self::distribute_payment(
+            env,
+            &config.token,
+            student,
+            &course.institution,
+            price,
+            config.fee_bps,
+        );
+    }
+
+    // Moves the course price into the contract for a waitlisted student and
+    // returns the token and amount held.
+    fn escrow_payment(env: &Env, student: &Address, course: &Course) -> (Option<Address>, i128) {
+        let config = match Self::get_fee_config(env.clone()) {
+            Some(config) => config,
+            None => return (None, 0),
+        };
+
+        let price = course.price as i128;
+        if price == 0 {
+            return (None, 0);
+        }
+
+        let token_client = token::Client::new(env, &config.token);
+        if token_client.balance(student) < price {
+            panic_with_error!(env, Error::InsufficientFunds);
+        }
+
+        token_client.transfer(student, &env.current_contract_address(), &price);
+        Self::adjust_escrowed_deposits(env, price);
+
+        (Some(config.token), price)
+    }
+
+    fn get_escrowed_deposits(env: &Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Bytes::from_slice(env, b"escrowed_deposits"))
+            .unwrap_or(0)
+    }
+
+    // Tracks the waitlist deposits currently held by the contract.
+    fn adjust_escrowed_deposits(env: &Env, delta: i128) {
+        let total = Self::get_escrowed_deposits(env) + delta;
+        env.storage()
+            .persistent()
+            .set(&Bytes::from_slice(env, b"escrowed_deposits"), &total);
+    }
+
+    fn distribute_payment(
+        env: &Env,
+        token: &Address,
+        from: &Address,
+        institution: &Address,
+        amount: i128,
+        fee_bps: u32,
+    ) {
+        let token_client = token::Client::new(env, token);
+        let contract_address = env.current_contract_address();
+
+        let fee = amount * fee_bps as i128 / MAX_FEE_BPS as i128;
+        let institution_share = amount - fee;
+
+        if institution_share > 0 {
+            token_client.transfer(from, institution, &institution_share);
+        }
+
+        if fee > 0 {
+            if *from != contract_address {
+                token_client.transfer(from, &contract_address, &fee);
+            }
+
+            let fees = Self::get_accumulated_fees(env.clone()) + fee;
+            env.storage()
+                .persistent()
+                .set(&Bytes::from_slice(env, b"accumulated_fees"), &fees);
+        }
+    }
+
+    // Drops an unfinished enrollment together with its entries in the
+    // `student_courses_` and `course_students_` indexes.
+    fn remove_enrollment(env: &Env, course_id: u64, student: &Address) {
+        let enrollment_key = Self::get_enrollment_key(env, &course_id, student);
+        let enrollment: Enrollment = env
+            .storage()
+            .persistent()
+            .get(&enrollment_key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::StudentNotEnrolled));
+
+        if enrollment.completed {
+            panic_with_error!(env, Error::EnrollmentCompleted);
+        }
+
+        env.storage().persistent().remove(&enrollment_key);
+
+        let student_courses_key = Self::get_student_courses_key(env, student);
+        let student_courses = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(&student_courses_key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut new_student_courses = Vec::new(env);
+        for id in student_courses.iter() {
+            if id != course_id {
+                new_student_courses.push_back(id);
+            }
+        }
+        env.storage().persistent().set(&student_courses_key, &new_student_courses);
+
+        let course_students_key = Self::get_course_students_key(env, &course_id);
+        let course_students = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<Address>>(&course_students_key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut new_course_students = Vec::new(env);
+        for address in course_students.iter() {
+            if address != *student {
+                new_course_students.push_back(address);
+            }
+        }
+        env.storage().persistent().set(&course_students_key, &new_course_students);
+
+        if let Some(course) = env.storage().persistent().get::<u64, Course>(&course_id) {
+            Self::promote_from_waitlist(env, &course);
+        }
+    }
+
+    fn add_enrollment(env: &Env, course_id: u64, student: &Address) {
+        let enrollment = Enrollment {
+            student: student.clone(),
+            course_id,
+            enrolled_at: env.ledger().timestamp(),
+            completed: false,
+            completed_version: None,
+            completed_milestones: Vec::new(env),
+            score: None,
+            honours: Honours::None,
+        };
+
+        let enrollment_key = Self::get_enrollment_key(env, &course_id, student);
+        env.storage().persistent().set(&enrollment_key, &enrollment);
+
+        let student_courses_key = Self::get_student_courses_key(env, student);
+        let mut student_courses = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(&student_courses_key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        student_courses.push_back(course_id);
+        env.storage().persistent().set(&student_courses_key, &student_courses);
+
+        let course_students_key = Self::get_course_students_key(env, &course_id);
+        let mut course_students = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<Address>>(&course_students_key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        course_students.push_back(student.clone());
+        env.storage().persistent().set(&course_students_key, &course_students);
+    }
+
+    // Checks that a student may complete the course with the given score and
+    // returns their enrollment. Nothing is written so batches can skip a
+    // failing student without side effects.
+    fn check_completion(
+        env: &Env,
+        course: &Course,
+        student: &Address,
+        score: u32,
+    ) -> Result<Enrollment, Error> {
+        if course.status == CourseStatus::Archived {
+            return Err(Error::CourseArchived);
+        }
+
+        let now = env.ledger().timestamp();
+        if course.schedule.starts_at.is_some_and(|starts_at| now < starts_at) {
+            return Err(Error::CourseNotStarted);
+        }
+        if course.schedule.ends_at.is_some_and(|ends_at| now > ends_at) {
+            return Err(Error::CourseEnded);
+        }
+
+        let enrollment: Enrollment = env
+            .storage()
+            .persistent()
+            .get(&Self::get_enrollment_key(env, &course.id, student))
+            .ok_or(Error::StudentNotEnrolled)?;
+
+        if enrollment.completed {
+            return Err(Error::EnrollmentCompleted);
+        }
+
+        if Self::count_required_completed(course, &enrollment) < Self::count_required(course) {
+            return Err(Error::MilestonesIncomplete);
+        }
+
+        if score > MAX_SCORE {
+            return Err(Error::InvalidScore);
+        }
+        if score < course.passing_score {
+            return Err(Error::BelowPassingScore);
+        }
+
+        Ok(enrollment)
+    }
+
+    fn complete_one(
+        env: &Env,
+        course: &Course,
+        certificate_client: &CertificateClient,
+        completion: &CompletionRequest,
+    ) -> Result<u64, Error> {
+        let enrollment =
+            Self::check_completion(env, course, &completion.student, completion.score)?;
+
+        let certificate_id = certificate_client
+            .try_issue_certificate(
+                completion.student.clone(),
+                course.id,
+                course.institution.clone(),
+                course.metadata.clone(),
+                course.version,
+                completion.score,
+                completion.honours,
+                completion.content_hash.clone(),
+            )
+            .ok_or(Error::CertificateIssuanceFailed)?;
+
+        Self::mark_completed(env, course, enrollment, completion.score, completion.honours);
+
+        Ok(certificate_id)
+    }
+
+    // Checks the whole cohort before issuing anything, then issues every
+    // certificate in one call to the certificate contract. Any failure
+    // aborts the transaction.
+    fn complete_all(
+        env: &Env,
+        course: &Course,
+        certificate_client: &CertificateClient,
+        completions: &Vec<CompletionRequest>,
+    ) -> Vec<CompletionResult> {
+        let mut enrollments = Vec::new(env);
+        let mut requests = Vec::new(env);
+        for completion in completions.iter() {
+            let enrollment =
+                Self::check_completion(env, course, &completion.student, completion.score)
+                    .unwrap_or_else(|error| panic_with_error!(env, error));
+
+            // A student listed twice would be completed twice
+            let listed_twice = enrollments
+                .iter()
+                .any(|previous: Enrollment| previous.student == completion.student);
+            if listed_twice {
+                panic_with_error!(env, Error::EnrollmentCompleted);
+            }
+
+            enrollments.push_back(enrollment);
+            requests.push_back(CertificateRequest {
+                student: completion.student.clone(),
+                metadata: course.metadata.clone(),
+                score: completion.score,
+                honours: completion.honours,
+                content_hash: completion.content_hash.clone(),
+            });
+        }
+
+        let certificate_ids = certificate_client.issue_certificates_batch(
+            course.institution.clone(),
+            course.id,
+            course.version,
+            requests,
+        );
+
+        let mut results = Vec::new(env);
+        for (i, completion) in completions.iter().enumerate() {
+            let enrollment = enrollments.get_unchecked(i as u32);
+            Self::mark_completed(env, course, enrollment, completion.score, completion.honours);
+            results.push_back(CompletionResult {
+                student: completion.student,
+                certificate_id: Some(certificate_ids.get_unchecked(i as u32)),
+                error: None,
+            });
+        }
+
+        results
+    }
+
+    fn mark_completed(
+        env: &Env,
+        course: &Course,
+        mut enrollment: Enrollment,
+        score: u32,
+        honours: Honours,
+    ) {
+        enrollment.completed = true;
+        enrollment.completed_version = Some(course.version);
+        enrollment.score = Some(score);
+        enrollment.honours = honours;
+
+        let enrollment_key = Self::get_enrollment_key(env, &course.id, &enrollment.student);
+        env.storage().persistent().set(&enrollment_key, &enrollment);
+
+        let completed_count_key = Self::get_completed_count_key(env, &course.id);
+        let completed = Self::get_completed_count(env, &course.id);
+        env.storage().persistent().set(&completed_count_key, &(completed + 1));
+    }
+
+    fn get_completed_count(env: &Env, course_id: &u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_completed_count_key(env, course_id))
+            .unwrap_or(0)
+    }
+
+    fn count_required(course: &Course) -> u32 {
+        course
+            .milestones
+            .iter()
+            .filter(|milestone| milestone.required)
+            .count() as u32
+    }
+
+    fn count_required_completed(course: &Course, enrollment: &Enrollment) -> u32 {
+        course
+            .milestones
+            .iter()
+            .filter(|milestone| {
+                milestone.required && enrollment.completed_milestones.contains(milestone.id)
+            })
+            .count() as u32
+    }
+
+    fn check_enrollment_window(env: &Env, course: &Course) {
+        let now = env.ledger().timestamp();
+        if course.schedule.enrollment_opens_at.is_some_and(|opens_at| now < opens_at) {
+            panic_with_error!(env, Error::EnrollmentNotOpen);
+        }
+        if course.schedule.enrollment_closes_at.is_some_and(|closes_at| now > closes_at) {
+            panic_with_error!(env, Error::EnrollmentClosed);
+        }
+    }
+
+    // A prerequisite counts as met once the student's enrollment in that
+    // course has been completed.
+    fn check_prerequisites(env: &Env, course: &Course, student: &Address) {
+        for prerequisite_id in course.prerequisites.iter() {
+            let enrollment_key = Self::get_enrollment_key(env, &prerequisite_id, student);
+            let completed = env
+                .storage()
+                .persistent()
+                .get::<Bytes, Enrollment>(&enrollment_key)
+                .is_some_and(|enrollment| enrollment.completed);
+
+            if !completed {
+                panic_with_error!(env, Error::PrerequisitesNotMet);
+            }
+        }
+    }
+
+    fn enrollment_window_contains(course: &Course, now: u64) -> bool {
+        course.schedule.enrollment_opens_at.is_none_or(|opens_at| now >= opens_at)
+            && course.schedule.enrollment_closes_at.is_none_or(|closes_at| now <= closes_at)
+    }
+
+    // Completed enrollments stay on the course's student list for good, so
+    // only the students still taking the course hold a seat.
+    fn is_full(env: &Env, course: &Course) -> bool {
+        match course.max_students {
+            Some(max_students) => {
+                let course_students_key = Self::get_course_students_key(env, &course.id);
+                let enrolled = env
+                    .storage()
+                    .persistent()
+                    .get::<Bytes, Vec<Address>>(&course_students_key)
+                    .map_or(0, |students| students.len());
+                let completed = Self::get_completed_count(env, &course.id);
+                enrolled.saturating_sub(completed) >= max_students
+            }
+            None => false,
+        }
+    }
+
+    // Fills free seats from the front of the waitlist, releasing each
+    // promoted student's deposit to the institution and the platform.
+    fn promote_from_waitlist(env: &Env, course: &Course) {
+        if course.status == CourseStatus::Archived {
+            return;
+        }
+
+        let waitlist_key = Self::get_waitlist_key(env, &course.id);
+        let mut waitlist = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<WaitlistEntry>>(&waitlist_key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        if waitlist.is_empty() {
+            return;
+        }
+
+        while !waitlist.is_empty() && !Self::is_full(env, course) {
+            let entry = waitlist.pop_front_unchecked();
+            Self::add_enrollment(env, course.id, &entry.student);
+
+            if let Some(token) = entry.token {
+                Self::adjust_escrowed_deposits(env, -entry.deposit);
+                let fee_bps = Self::get_fee_config(env.clone()).map_or(0, |config| config.fee_bps);
+                Self::distribute_payment(
+                    env,
+                    &token,
+                    &env.current_contract_address(),
+                    &course.institution,
+                    entry.deposit,
+                    fee_bps,
+                );
+            }
+        }
+
+        env.storage().persistent().set(&waitlist_key, &waitlist);
+    }
+
+    fn get_enrollment_key(env: &Env, course_id: &u64, student: &Address) -> Bytes {
+        let mut key = Bytes::new(env);
         key.append(&Bytes::from_slice(env, b"enrollment_"));
         key.append(&Bytes::from_array(env, &course_id.to_le_bytes()));
         key.append(&Bytes::from_slice(env, b"_"));
@@ -332,10 +1767,142 @@ impl CourseContract {
         key
     }
 
+    fn get_course_version_key(env: &Env, course_id: &u64, version: u32) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"course_version_"));
+        key.append(&Bytes::from_array(env, &course_id.to_le_bytes()));
+        key.append(&Bytes::from_slice(env, b"_"));
+        key.append(&Bytes::from_array(env, &version.to_le_bytes()));
+        key
+    }
+
+    fn load_course_page(env: &Env, key: &Bytes, start: u32, limit: u32) -> Vec<Course> {
+        let course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut courses = Vec::new(env);
+        let end = course_ids.len().min(start.saturating_add(limit));
+        for i in start..end {
+            let course_id = course_ids.get(i).unwrap();
+            if let Some(course) = env.storage().persistent().get::<u64, Course>(&course_id) {
+                courses.push_back(course);
+            }
+        }
+
+        courses
+    }
+
+    fn add_to_index(env: &Env, key: &Bytes, course_id: u64) {
+        let mut course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        course_ids.push_back(course_id);
+        env.storage().persistent().set(key, &course_ids);
+    }
+
+    fn remove_from_index(env: &Env, key: &Bytes, course_id: u64) {
+        let course_ids = env
+            .storage()
+            .persistent()
+            .get::<Bytes, Vec<u64>>(key)
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut new_course_ids = Vec::new(env);
+        for id in course_ids.iter() {
+            if id != course_id {
+                new_course_ids.push_back(id);
+            }
+        }
+        env.storage().persistent().set(key, &new_course_ids);
+    }
+
+    // The price index holds (price, course_id) pairs sorted in ascending
+    // order so price range queries only read the courses they return.
+    fn get_price_index(env: &Env) -> Vec<(u64, u64)> {
+        env.storage()
+            .persistent()
+            .get::<Bytes, Vec<(u64, u64)>>(&Bytes::from_slice(env, b"price_index"))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    // Position of the first entry not less than `entry`.
+    fn price_index_position(index: &Vec<(u64, u64)>, entry: (u64, u64)) -> u32 {
+        let mut low = 0;
+        let mut high = index.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if index.get_unchecked(mid) < entry {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    fn add_to_price_index(env: &Env, price: u64, course_id: u64) {
+        let mut index = Self::get_price_index(env);
+        let position = Self::price_index_position(&index, (price, course_id));
+        index.insert(position, (price, course_id));
+        env.storage()
+            .persistent()
+            .set(&Bytes::from_slice(env, b"price_index"), &index);
+    }
+
+    fn remove_from_price_index(env: &Env, price: u64, course_id: u64) {
+        let mut index = Self::get_price_index(env);
+        let position = Self::price_index_position(&index, (price, course_id));
+        if index.get(position) == Some((price, course_id)) {
+            index.remove(position);
+            env.storage()
+                .persistent()
+                .set(&Bytes::from_slice(env, b"price_index"), &index);
+        }
+    }
+
+    fn get_institution_courses_key(env: &Env, institution: &Address) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"institution_courses_"));
+
+        key.append(&Bytes::from_slice(env, b"address_"));
+
+        let address_bytes = Bytes::from_slice(env, institution.to_string().to_string().as_bytes());
+        key.append(&address_bytes);
+
+        key
+    }
+
+    fn get_status_courses_key(env: &Env, status: CourseStatus) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"status_courses_"));
+        key.append(&Bytes::from_array(env, &(status as u32).to_le_bytes()));
+        key
+    }
+
+    fn get_waitlist_key(env: &Env, course_id: &u64) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"waitlist_"));
+        key.append(&Bytes::from_array(env, &course_id.to_le_bytes()));
+        key
+    }
+
     fn get_course_students_key(env: &Env, course_id: &u64) -> Bytes {
         let mut key = Bytes::new(env);
         key.append(&Bytes::from_slice(env, b"course_students_"));
         key.append(&Bytes::from_array(env, &course_id.to_le_bytes()));
         key
     }
+
+    fn get_completed_count_key(env: &Env, course_id: &u64) -> Bytes {
+        let mut key = Bytes::new(env);
+        key.append(&Bytes::from_slice(env, b"completed_count_"));
+        key.append(&Bytes::from_array(env, &course_id.to_le_bytes()));
+        key
+    }
 }
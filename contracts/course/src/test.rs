@@ -1,11 +1,16 @@
 #![cfg(test)]
+#![allow(clippy::too_many_arguments)]
 
-use crate::course::{CourseContract, CourseContractClient, Course, Error};
+use crate::course::{
+    CertificateRequest, CompletionRequest, CourseContract, CourseContractClient, Course, CourseSchedule, CourseStatus,
+    Error, Honours, Milestone, MAX_COMPLETION_BATCH,
+};
 
 use soroban_sdk::{
-    testutils::{Address as _},
-    Bytes, Map, Val, IntoVal, Symbol, 
-    Address, Env, contract, contractimpl
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Bytes, BytesN, Map, Val, IntoVal, Symbol, 
+    Address, Env, Vec, contract, contractimpl, vec
 };
 
 
@@ -37,20 +42,44 @@ impl InstitutionContractMock {
     }
 }
 
+fn document_hash(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[7; 32])
+}
+
 #[contract]
 struct CertificateContractMock;
 
 #[contractimpl]
 impl CertificateContractMock {
     pub fn issue_certificate(
-        _env: Env,
+        env: Env,
         _student: Address,
         course_id: u64,
         _institution: Address,
         _metadata: Bytes,
+        _course_version: u32,
+        score: u32,
+        honours: Honours,
+        _content_hash: BytesN<32>,
     ) -> u64 {
+        env.storage().persistent().set(&Symbol::new(&env, "score"), &score);
+        env.storage().persistent().set(&Symbol::new(&env, "honours"), &honours);
         course_id + 1000
     }
+
+    pub fn issue_certificates_batch(
+        env: Env,
+        _institution: Address,
+        course_id: u64,
+        _course_version: u32,
+        requests: Vec<CertificateRequest>,
+    ) -> Vec<u64> {
+        let mut ids = Vec::new(&env);
+        for i in 0..requests.len() {
+            ids.push_back(course_id + 2000 + i as u64);
+        }
+        ids
+    }
 }
 
 #[test]
@@ -89,7 +118,8 @@ fn test_create_course() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     let course = client.get_course(&course_id);
@@ -126,7 +156,8 @@ fn test_enroll_duplicate_panics() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     client.enroll_in_course(&course_id, &student);
@@ -160,14 +191,31 @@ fn test_complete_course() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     client.enroll_in_course(&course_id, &student);
     
-    client.complete_course(&course_id, &student, &certificate_contract);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
     
-    client.complete_course(&course_id, &student, &certificate_contract);
+    // A completed enrollment cannot be completed a second time
+    let result = client.try_complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    assert_eq!(result.err(), Some(Ok(Error::EnrollmentCompleted.into())));
 }
 
 #[test]
@@ -193,7 +241,8 @@ fn test_remove_course() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     let courses = client.list_courses();
@@ -239,7 +288,8 @@ fn test_remove_course_with_enrolled_students() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     client.enroll_in_course(&course_id, &student);
@@ -272,7 +322,8 @@ fn test_unauthorized_operations() {
         &price,
         &metadata,
         &certificate_id,
-        &institution_contract
+        &institution_contract,
+        &Vec::new(&env)
     );
     
     client.remove_course(&course_id, &unauthorized_institution);
@@ -302,6 +353,1516 @@ fn test_unverified_institution() {
         &price,
         &metadata,
         &certificate_id,
-        &unverified_institution_contract
+        &unverified_institution_contract,
+        &Vec::new(&env)
+    );
+}
+
+
+fn create_token(env: &Env, student: &Address, amount: i128) -> Address {
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(token_admin).address();
+    StellarAssetClient::new(env, &token).mint(student, &amount);
+    token
+}
+
+#[test]
+fn test_enrollment_payment_is_split_with_treasury() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let admin = Address::generate(&env);
+    let institution = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let token = create_token(&env, &student, 1_000);
+    let token_client = TokenClient::new(&env, &token);
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(&admin, &token, &treasury, &250);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Paid Course"),
+        &institution,
+        &400,
+        &Bytes::from_slice(&env, b"ipfs://QmPaidCourse"),
+        &7,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    
+    assert_eq!(token_client.balance(&student), 600);
+    assert_eq!(token_client.balance(&institution), 390);
+    assert_eq!(token_client.balance(&course_contract), 10);
+    assert_eq!(client.get_accumulated_fees(), 10);
+    
+    let withdrawn = client.withdraw_fees(&admin);
+    
+    assert_eq!(withdrawn, 10);
+    assert_eq!(token_client.balance(&treasury), 10);
+    assert_eq!(client.get_accumulated_fees(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_enrollment_with_insufficient_funds() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let admin = Address::generate(&env);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let token = create_token(&env, &student, 50);
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(&admin, &token, &Address::generate(&env), &500);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Expensive Course"),
+        &institution,
+        &100,
+        &Bytes::from_slice(&env, b"ipfs://QmExpensiveCourse"),
+        &8,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_fee_above_one_hundred_percent_rejected() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    let admin = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(
+        &admin,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &10_001
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_non_admin_cannot_withdraw_fees() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    let admin = Address::generate(&env);
+    let non_admin = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(&admin, &Address::generate(&env), &Address::generate(&env), &100);
+    
+    client.withdraw_fees(&non_admin);
+}
+
+#[test]
+fn test_unenroll_allows_course_removal() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Short Lived Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmShortLived"),
+        &9,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.unenroll(&course_id, &student);
+    
+    // The student can come back after leaving
+    client.enroll_in_course(&course_id, &student);
+    client.unenroll(&course_id, &student);
+    
+    client.remove_course(&course_id, &institution);
+    assert_eq!(client.list_courses().len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_cannot_unenroll_after_completion() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Finished Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmFinished"),
+        &10,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    
+    client.unenroll(&course_id, &student);
+}
+
+#[test]
+fn test_withdraw_student() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Strict Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmStrict"),
+        &11,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.withdraw_student(&course_id, &student, &institution);
+    
+    let result = client.try_unenroll(&course_id, &student);
+    assert_eq!(result.err(), Some(Ok(Error::StudentNotEnrolled.into())));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_other_institution_cannot_withdraw_student() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let other_institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Guarded Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmGuarded"),
+        &12,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.withdraw_student(&course_id, &student, &other_institution);
+}
+
+#[test]
+fn test_course_lifecycle() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    let late_student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Seasonal Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmSeasonal"),
+        &13,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    assert_eq!(client.get_course(&course_id).status, CourseStatus::Published);
+    
+    client.enroll_in_course(&course_id, &student);
+    
+    client.set_course_status(&course_id, &institution, &CourseStatus::Closed);
+    let result = client.try_enroll_in_course(&course_id, &late_student);
+    assert_eq!(result.err(), Some(Ok(Error::CourseNotOpen.into())));
+    
+    // Enrolled students can still finish a closed course
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    
+    client.set_course_status(&course_id, &institution, &CourseStatus::Archived);
+    
+    let course = client.get_course(&course_id);
+    assert_eq!(course.status, CourseStatus::Archived);
+    assert_eq!(client.list_courses().len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_archived_course_cannot_be_reopened() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Retired Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmRetired"),
+        &14,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.set_course_status(&course_id, &institution, &CourseStatus::Archived);
+    client.set_course_status(&course_id, &institution, &CourseStatus::Published);
+}
+
+#[test]
+fn test_archived_course_is_frozen() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Retired Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmRetired"),
+        &14,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_course_milestones(
+        &course_id,
+        &institution,
+        &vec![&env, Milestone { id: 1, title: Bytes::from_slice(&env, b"Quiz"), required: true }],
+    );
+    client.enroll_in_course(&course_id, &student);
+    client.set_course_status(&course_id, &institution, &CourseStatus::Closed);
+    client.set_course_status(&course_id, &institution, &CourseStatus::Archived);
+    
+    let archived = Some(Ok(Error::CourseArchived.into()));
+    assert_eq!(client.try_set_max_students(&course_id, &institution, &Some(5)).err(), archived);
+    assert_eq!(
+        client.try_set_course_schedule(
+            &course_id,
+            &institution,
+            &CourseSchedule {
+                enrollment_opens_at: None,
+                enrollment_closes_at: None,
+                starts_at: None,
+                ends_at: None,
+            },
+        ).err(),
+        archived
+    );
+    assert_eq!(
+        client.try_set_course_milestones(&course_id, &institution, &Vec::new(&env)).err(),
+        archived
+    );
+    assert_eq!(client.try_set_passing_score(&course_id, &institution, &50).err(), archived);
+    assert_eq!(client.try_record_progress(&course_id, &student, &1).err(), archived);
+    assert_eq!(client.try_withdraw_student(&course_id, &student, &institution).err(), archived);
+    assert_eq!(client.try_unenroll(&course_id, &student).err(), archived);
+    
+    // Enrollments stay readable
+    assert_eq!(client.get_enrollment(&course_id, &student).student, student);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_cannot_unpublish_course_with_students() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Running Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmRunning"),
+        &15,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.set_course_status(&course_id, &institution, &CourseStatus::Draft);
+}
+
+#[test]
+fn test_update_course_keeps_version_history() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let original_title = Bytes::from_slice(&env, b"Intro to Soroban");
+    let course_id = client.create_course(
+        &original_title,
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmIntroV1"),
+        &16,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    env.ledger().set_timestamp(1_700_000_000);
+    
+    let new_title = Bytes::from_slice(&env, b"Intro to Soroban (2nd edition)");
+    let version = client.update_course(
+        &course_id,
+        &institution,
+        &new_title,
+        &25,
+        &Bytes::from_slice(&env, b"ipfs://QmIntroV2")
+    );
+    assert_eq!(version, 2);
+    
+    let course = client.get_course(&course_id);
+    assert_eq!(course.title, new_title);
+    assert_eq!(course.price, 25);
+    assert_eq!(course.version, 2);
+    
+    let first = client.get_course_version(&course_id, &1);
+    assert_eq!(first.title, original_title);
+    assert_eq!(first.price, 10);
+    assert_eq!(first.replaced_at, Some(1_700_000_000));
+    
+    let current = client.get_course_version(&course_id, &2);
+    assert_eq!(current.effective_from, 1_700_000_000);
+    assert_eq!(current.replaced_at, None);
+    assert_eq!(client.list_course_versions(&course_id).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_other_institution_cannot_update_course() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let other_institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Owned Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmOwned"),
+        &17,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.update_course(
+        &course_id,
+        &other_institution,
+        &Bytes::from_slice(&env, b"Hijacked"),
+        &0,
+        &Bytes::new(&env)
+    );
+}
+
+#[test]
+fn test_full_course_promotes_waitlist_in_order() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Small Seminar"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmSeminar"),
+        &18,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_max_students(&course_id, &institution, &Some(1));
+    
+    client.enroll_in_course(&course_id, &first);
+    
+    let result = client.try_enroll_in_course(&course_id, &second);
+    assert_eq!(result.err(), Some(Ok(Error::CourseFull.into())));
+    
+    client.join_waitlist(&course_id, &second);
+    client.join_waitlist(&course_id, &third);
+    assert_eq!(client.get_waitlist(&course_id).len(), 2);
+    
+    client.unenroll(&course_id, &first);
+    
+    let waitlist = client.get_waitlist(&course_id);
+    assert_eq!(waitlist.len(), 1);
+    assert_eq!(waitlist.get(0).unwrap().student, third);
+    
+    // `second` now holds the only seat
+    let result = client.try_enroll_in_course(&course_id, &second);
+    assert_eq!(result.err(), Some(Ok(Error::StudentAlreadyEnrolled.into())));
+    
+    client.set_max_students(&course_id, &institution, &None);
+    assert_eq!(client.get_waitlist(&course_id).len(), 0);
+}
+
+#[test]
+fn test_completed_students_free_their_seats() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Small Seminar"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmSeminar"),
+        &18,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_max_students(&course_id, &institution, &Some(1));
+    
+    client.enroll_in_course(&course_id, &first);
+    client.join_waitlist(&course_id, &second);
+    
+    client.complete_course(
+        &course_id,
+        &first,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    
+    // Completing `first` hands the seat to the head of the waitlist
+    assert_eq!(client.get_waitlist(&course_id).len(), 0);
+    let result = client.try_enroll_in_course(&course_id, &second);
+    assert_eq!(result.err(), Some(Ok(Error::StudentAlreadyEnrolled.into())));
+    
+    let result = client.try_enroll_in_course(&course_id, &third);
+    assert_eq!(result.err(), Some(Ok(Error::CourseFull.into())));
+}
+
+#[test]
+fn test_waitlist_deposit_is_released_or_refunded() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let admin = Address::generate(&env);
+    let institution = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let token = create_token(&env, &first, 100);
+    let token_client = TokenClient::new(&env, &token);
+    let token_admin = StellarAssetClient::new(&env, &token);
+    token_admin.mint(&second, &100);
+    token_admin.mint(&third, &100);
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(&admin, &token, &treasury, &1_000);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Popular Workshop"),
+        &institution,
+        &100,
+        &Bytes::from_slice(&env, b"ipfs://QmWorkshop"),
+        &19,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_max_students(&course_id, &institution, &Some(1));
+    
+    client.enroll_in_course(&course_id, &first);
+    client.join_waitlist(&course_id, &second);
+    client.join_waitlist(&course_id, &third);
+    assert_eq!(token_client.balance(&course_contract), 210);
+    
+    client.leave_waitlist(&course_id, &third);
+    assert_eq!(token_client.balance(&third), 100);
+    
+    client.withdraw_student(&course_id, &first, &institution);
+    
+    assert_eq!(token_client.balance(&institution), 180);
+    assert_eq!(token_client.balance(&course_contract), 20);
+    assert_eq!(client.get_accumulated_fees(), 20);
+}
+
+#[test]
+fn test_fee_token_locked_while_funds_held() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let admin = Address::generate(&env);
+    let institution = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let token = create_token(&env, &first, 100);
+    StellarAssetClient::new(&env, &token).mint(&second, &100);
+    let other_token = create_token(&env, &first, 0);
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    client.set_admin(&admin);
+    client.set_fee_config(&admin, &token, &treasury, &1_000);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Popular Workshop"),
+        &institution,
+        &100,
+        &Bytes::from_slice(&env, b"ipfs://QmWorkshop"),
+        &19,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_max_students(&course_id, &institution, &Some(1));
+    
+    // Accumulated fees and the waitlist deposit are both held in `token`
+    client.enroll_in_course(&course_id, &first);
+    client.join_waitlist(&course_id, &second);
+    
+    let result = client.try_set_fee_config(&admin, &other_token, &treasury, &1_000);
+    assert_eq!(result.err(), Some(Ok(Error::FeeTokenLocked.into())));
+    
+    // The fee rate can still change as long as the token stays the same
+    client.set_fee_config(&admin, &token, &treasury, &500);
+    
+    // Withdrawing fees is not enough while the deposit is still held
+    client.withdraw_fees(&admin);
+    let result = client.try_set_fee_config(&admin, &other_token, &treasury, &1_000);
+    assert_eq!(result.err(), Some(Ok(Error::FeeTokenLocked.into())));
+    
+    client.leave_waitlist(&course_id, &second);
+    client.set_fee_config(&admin, &other_token, &treasury, &1_000);
+    assert_eq!(client.get_fee_config().unwrap().token, other_token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_cannot_join_waitlist_with_free_seats() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Open Lecture"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmOpenLecture"),
+        &20,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.join_waitlist(&course_id, &student);
+}
+
+#[test]
+fn test_enrollment_window_and_schedule() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Spring Term"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmSpringTerm"),
+        &21,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_course_schedule(&course_id, &institution, &CourseSchedule {
+        enrollment_opens_at: Some(2_000),
+        enrollment_closes_at: Some(3_000),
+        starts_at: Some(4_000),
+        ends_at: Some(5_000),
+    });
+    
+    assert_eq!(client.list_open_courses().len(), 0);
+    let result = client.try_enroll_in_course(&course_id, &student);
+    assert_eq!(result.err(), Some(Ok(Error::EnrollmentNotOpen.into())));
+    
+    env.ledger().set_timestamp(2_500);
+    assert_eq!(client.list_open_courses().len(), 1);
+    client.enroll_in_course(&course_id, &student);
+    
+    env.ledger().set_timestamp(3_500);
+    assert_eq!(client.list_open_courses().len(), 0);
+    let result = client.try_enroll_in_course(&course_id, &Address::generate(&env));
+    assert_eq!(result.err(), Some(Ok(Error::EnrollmentClosed.into())));
+    
+    let result = client.try_complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    assert_eq!(result.err(), Some(Ok(Error::CourseNotStarted.into())));
+    
+    env.ledger().set_timestamp(5_500);
+    let result = client.try_complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    assert_eq!(result.err(), Some(Ok(Error::CourseEnded.into())));
+    
+    env.ledger().set_timestamp(4_500);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_schedule_must_be_ordered() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Backwards Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmBackwards"),
+        &22,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_course_schedule(&course_id, &institution, &CourseSchedule {
+        enrollment_opens_at: None,
+        enrollment_closes_at: None,
+        starts_at: Some(5_000),
+        ends_at: Some(4_000),
+    });
+}
+
+#[test]
+fn test_prerequisites_enforced_on_enrollment() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let basics_id = client.create_course(
+        &Bytes::from_slice(&env, b"Rust Basics"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmRustBasics"),
+        &23,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    let advanced_id = client.create_course(
+        &Bytes::from_slice(&env, b"Advanced Rust"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmAdvancedRust"),
+        &24,
+        &institution_contract,
+        &vec![&env, basics_id]
+    );
+    assert_ne!(basics_id, advanced_id);
+    assert_eq!(client.get_course(&advanced_id).prerequisites, vec![&env, basics_id]);
+    
+    let result = client.try_enroll_in_course(&advanced_id, &student);
+    assert_eq!(result.err(), Some(Ok(Error::PrerequisitesNotMet.into())));
+    
+    // Being enrolled is not enough, the prerequisite must be completed
+    client.enroll_in_course(&basics_id, &student);
+    let result = client.try_enroll_in_course(&advanced_id, &student);
+    assert_eq!(result.err(), Some(Ok(Error::PrerequisitesNotMet.into())));
+    
+    client.complete_course(
+        &basics_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    client.enroll_in_course(&advanced_id, &student);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_unknown_prerequisite_rejected() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    client.create_course(
+        &Bytes::from_slice(&env, b"Orphan Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmOrphan"),
+        &25,
+        &institution_contract,
+        &vec![&env, 999]
+    );
+}
+
+#[test]
+fn test_milestone_progress_gates_completion() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Modular Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmModular"),
+        &26,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_course_milestones(&course_id, &institution, &vec![
+        &env,
+        Milestone { id: 1, title: Bytes::from_slice(&env, b"Module 1"), required: true },
+        Milestone { id: 2, title: Bytes::from_slice(&env, b"Module 2"), required: true },
+        Milestone { id: 3, title: Bytes::from_slice(&env, b"Bonus"), required: false },
+    ]);
+    
+    client.enroll_in_course(&course_id, &student);
+    client.record_progress(&course_id, &student, &1);
+    client.record_progress(&course_id, &student, &3);
+    client.record_progress(&course_id, &student, &1);
+    
+    let progress = client.get_progress(&course_id, &student);
+    assert_eq!(progress.completed_milestones, vec![&env, 1, 3]);
+    assert_eq!(progress.required_completed, 1);
+    assert_eq!(progress.required_total, 2);
+    assert!(!progress.completed);
+    
+    let result = client.try_complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    assert_eq!(result.err(), Some(Ok(Error::MilestonesIncomplete.into())));
+    
+    client.record_progress(&course_id, &student, &2);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &100,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    
+    let progress = client.get_progress(&course_id, &student);
+    assert_eq!(progress.required_completed, 2);
+    assert!(progress.completed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_record_unknown_milestone() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Unstructured Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmUnstructured"),
+        &27,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.record_progress(&course_id, &student, &1);
+}
+
+#[test]
+fn test_graded_completion_reaches_certificate() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    let failing_student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Graded Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmGraded"),
+        &28,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_passing_score(&course_id, &institution, &60);
+    
+    client.enroll_in_course(&course_id, &failing_student);
+    let result = client.try_complete_course(
+        &course_id,
+        &failing_student,
+        &certificate_contract,
+        &59,
+        &Honours::None,
+        &document_hash(&env)
+    );
+    assert_eq!(result.err(), Some(Ok(Error::BelowPassingScore.into())));
+    
+    client.enroll_in_course(&course_id, &student);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &92,
+        &Honours::Distinction,
+        &document_hash(&env)
+    );
+    
+    let (score, honours): (u32, Honours) = env.as_contract(&certificate_contract, || {
+        (
+            env.storage().persistent().get(&Symbol::new(&env, "score")).unwrap(),
+            env.storage().persistent().get(&Symbol::new(&env, "honours")).unwrap(),
+        )
+    });
+    assert_eq!(score, 92);
+    assert_eq!(honours, Honours::Distinction);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_score_above_scale_rejected() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Overachiever Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmOverachiever"),
+        &29,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&course_id, &student);
+    client.complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &101,
+        &Honours::None,
+        &document_hash(&env)
+    );
+}
+
+#[test]
+fn test_enrollment_queries() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    let classmate = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let first_id = client.create_course(
+        &Bytes::from_slice(&env, b"First Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmFirst"),
+        &30,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    let second_id = client.create_course(
+        &Bytes::from_slice(&env, b"Second Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmSecond"),
+        &31,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    client.enroll_in_course(&first_id, &student);
+    client.enroll_in_course(&first_id, &classmate);
+    client.enroll_in_course(&second_id, &student);
+    client.complete_course(
+        &first_id,
+        &student,
+        &certificate_contract,
+        &80,
+        &Honours::Merit,
+        &document_hash(&env)
+    );
+    
+    let enrollment = client.get_enrollment(&first_id, &student);
+    assert!(enrollment.completed);
+    assert_eq!(enrollment.completed_version, Some(1));
+    assert_eq!(enrollment.score, Some(80));
+    assert_eq!(enrollment.honours, Honours::Merit);
+    
+    let enrollments = client.list_student_enrollments(&student, &0, &10);
+    assert_eq!(enrollments.len(), 2);
+    assert_eq!(enrollments.get(0).unwrap().course_id, first_id);
+    assert_eq!(enrollments.get(1).unwrap().course_id, second_id);
+    
+    let page = client.list_student_enrollments(&student, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().course_id, second_id);
+    
+    assert_eq!(client.list_course_students(&first_id, &0, &1), vec![&env, student.clone()]);
+    assert_eq!(client.list_course_students(&first_id, &1, &5), vec![&env, classmate.clone()]);
+    assert_eq!(client.list_course_students(&first_id, &2, &5).len(), 0);
+    
+    client.unenroll(&second_id, &student);
+    assert_eq!(client.list_student_enrollments(&student, &0, &10).len(), 1);
+    let result = client.try_get_enrollment(&second_id, &student);
+    assert_eq!(result.err(), Some(Ok(Error::StudentNotEnrolled.into())));
+}
+
+#[test]
+fn test_catalog_filters() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let institution = Address::generate(&env);
+    let other_institution = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let cheap_id = client.create_course(
+        &Bytes::from_slice(&env, b"Cheap Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmCheap"),
+        &32,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    let mid_id = client.create_course(
+        &Bytes::from_slice(&env, b"Mid Course"),
+        &institution,
+        &50,
+        &Bytes::from_slice(&env, b"ipfs://QmMid"),
+        &33,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    let premium_id = client.create_course(
+        &Bytes::from_slice(&env, b"Premium Course"),
+        &other_institution,
+        &200,
+        &Bytes::from_slice(&env, b"ipfs://QmPremium"),
+        &34,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    
+    let own = client.list_courses_by_institution(&institution, &0, &10);
+    assert_eq!(own.len(), 2);
+    assert_eq!(own.get(0).unwrap().id, cheap_id);
+    assert_eq!(own.get(1).unwrap().id, mid_id);
+    assert_eq!(client.list_courses_by_institution(&institution, &1, &10).len(), 1);
+    
+    let affordable = client.list_courses_by_price(&0, &60, &0, &10);
+    assert_eq!(affordable.len(), 2);
+    let page = client.list_courses_by_price(&0, &1_000, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().id, mid_id);
+    
+    client.set_course_status(&premium_id, &other_institution, &CourseStatus::Closed);
+    assert_eq!(client.list_courses_by_status(&CourseStatus::Published, &0, &10).len(), 2);
+    let closed = client.list_courses_by_status(&CourseStatus::Closed, &0, &10);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed.get(0).unwrap().id, premium_id);
+    
+    // Repricing moves the course within the price index
+    client.update_course(
+        &premium_id,
+        &other_institution,
+        &Bytes::from_slice(&env, b"Premium Course"),
+        &5,
+        &Bytes::from_slice(&env, b"ipfs://QmPremium"),
+    );
+    let by_price = client.list_courses_by_price(&0, &1_000, &0, &10);
+    assert_eq!(by_price.len(), 3);
+    assert_eq!(by_price.get(0).unwrap().id, premium_id);
+    assert_eq!(by_price.get(1).unwrap().id, cheap_id);
+    assert_eq!(client.list_courses_by_price(&100, &1_000, &0, &10).len(), 0);
+    
+    client.remove_course(&cheap_id, &institution);
+    assert_eq!(client.list_courses_by_institution(&institution, &0, &10).len(), 1);
+    assert_eq!(client.list_courses_by_status(&CourseStatus::Published, &0, &10).len(), 1);
+    let by_price = client.list_courses_by_price(&0, &1_000, &0, &10);
+    assert_eq!(by_price.len(), 2);
+    assert_eq!(by_price.get(1).unwrap().id, mid_id);
+}
+
+#[test]
+fn test_batch_completion_partial() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let first = Address::generate(&env);
+    let failing = Address::generate(&env);
+    let not_enrolled = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Cohort Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmCohort"),
+        &35,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_passing_score(&course_id, &institution, &50);
+    client.enroll_in_course(&course_id, &first);
+    client.enroll_in_course(&course_id, &failing);
+    
+    let completions = vec![
+        &env,
+        CompletionRequest {
+            student: first.clone(),
+            score: 75,
+            honours: Honours::Merit,
+            content_hash: document_hash(&env),
+        },
+        CompletionRequest {
+            student: failing.clone(),
+            score: 20,
+            honours: Honours::None,
+            content_hash: document_hash(&env),
+        },
+        CompletionRequest {
+            student: not_enrolled.clone(),
+            score: 90,
+            honours: Honours::None,
+            content_hash: document_hash(&env),
+        },
+    ];
+    
+    let results = client.complete_course_batch(
+        &course_id,
+        &completions,
+        &certificate_contract,
+        &false
+    );
+    assert_eq!(results.len(), 3);
+    
+    let passed = results.get(0).unwrap();
+    assert_eq!(passed.certificate_id, Some(course_id + 1000));
+    assert_eq!(passed.error, None);
+    
+    let below = results.get(1).unwrap();
+    assert_eq!(below.certificate_id, None);
+    assert_eq!(below.error, Some(Error::BelowPassingScore as u32));
+    assert_eq!(results.get(2).unwrap().error, Some(Error::StudentNotEnrolled as u32));
+    
+    assert!(client.get_enrollment(&course_id, &first).completed);
+    assert!(!client.get_enrollment(&course_id, &failing).completed);
+}
+
+#[test]
+fn test_completed_enrollment_cannot_complete_again() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Cohort Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmCohort"),
+        &37,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.enroll_in_course(&course_id, &student);
+    
+    let completions = vec![
+        &env,
+        CompletionRequest {
+            student: student.clone(),
+            score: 75,
+            honours: Honours::Merit,
+            content_hash: document_hash(&env),
+        },
+        CompletionRequest {
+            student: student.clone(),
+            score: 95,
+            honours: Honours::HighDistinction,
+            content_hash: document_hash(&env),
+        },
+    ];
+    
+    let results = client.complete_course_batch(
+        &course_id,
+        &completions,
+        &certificate_contract,
+        &false
+    );
+    assert_eq!(results.get(0).unwrap().certificate_id, Some(course_id + 1000));
+    let duplicate = results.get(1).unwrap();
+    assert_eq!(duplicate.certificate_id, None);
+    assert_eq!(duplicate.error, Some(Error::EnrollmentCompleted as u32));
+    
+    let result = client.try_complete_course(
+        &course_id,
+        &student,
+        &certificate_contract,
+        &95,
+        &Honours::HighDistinction,
+        &document_hash(&env),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::EnrollmentCompleted.into())));
+    
+    let enrollment = client.get_enrollment(&course_id, &student);
+    assert_eq!(enrollment.score, Some(75));
+    assert_eq!(enrollment.honours, Honours::Merit);
+}
+
+#[test]
+fn test_batch_completion_atomic() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let first = Address::generate(&env);
+    let failing = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Strict Cohort Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmStrictCohort"),
+        &36,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.set_passing_score(&course_id, &institution, &50);
+    client.enroll_in_course(&course_id, &first);
+    client.enroll_in_course(&course_id, &failing);
+    
+    let completions = vec![
+        &env,
+        CompletionRequest {
+            student: first.clone(),
+            score: 75,
+            honours: Honours::None,
+            content_hash: document_hash(&env),
+        },
+        CompletionRequest {
+            student: failing.clone(),
+            score: 20,
+            honours: Honours::None,
+            content_hash: document_hash(&env),
+        },
+    ];
+    
+    let result = client.try_complete_course_batch(
+        &course_id,
+        &completions,
+        &certificate_contract,
+        &true
+    );
+    assert_eq!(result.err(), Some(Ok(Error::BelowPassingScore.into())));
+    
+    // Nothing from the failed batch was kept
+    assert!(!client.get_enrollment(&course_id, &first).completed);
+    
+    // Listing a student twice fails the batch instead of completing twice
+    let duplicated = vec![&env, completions.get(0).unwrap(), completions.get(0).unwrap()];
+    let result = client.try_complete_course_batch(
+        &course_id,
+        &duplicated,
+        &certificate_contract,
+        &true
+    );
+    assert_eq!(result.err(), Some(Ok(Error::EnrollmentCompleted.into())));
+    
+    // A passing cohort is certified through one batch issuance
+    let results = client.complete_course_batch(
+        &course_id,
+        &vec![&env, completions.get(0).unwrap()],
+        &certificate_contract,
+        &true
+    );
+    assert_eq!(results.get(0).unwrap().certificate_id, Some(course_id + 2000));
+    assert!(client.get_enrollment(&course_id, &first).completed);
+}
+
+#[test]
+fn test_batch_completion_is_capped() {
+    let env = Env::default();
+    let course_contract = env.register(CourseContract, ());
+    
+    let institution_contract = mock_institution_fn(&env, true);
+    let certificate_contract = env.register(CertificateContractMock, ());
+    let institution = Address::generate(&env);
+    let student = Address::generate(&env);
+    
+    env.mock_all_auths();
+    
+    let client = CourseContractClient::new(&env, &course_contract);
+    
+    let course_id = client.create_course(
+        &Bytes::from_slice(&env, b"Large Cohort Course"),
+        &institution,
+        &10,
+        &Bytes::from_slice(&env, b"ipfs://QmLargeCohort"),
+        &38,
+        &institution_contract,
+        &Vec::new(&env)
+    );
+    client.enroll_in_course(&course_id, &student);
+    
+    let mut completions = Vec::new(&env);
+    for _ in 0..=MAX_COMPLETION_BATCH {
+        completions.push_back(CompletionRequest {
+            student: student.clone(),
+            score: 75,
+            honours: Honours::None,
+            content_hash: document_hash(&env),
+        });
+    }
+    
+    let result = client.try_complete_course_batch(
+        &course_id,
+        &completions,
+        &certificate_contract,
+        &false
     );
+    assert_eq!(result.err(), Some(Ok(Error::BatchTooLarge.into())));
+    assert!(!client.get_enrollment(&course_id, &student).completed);
 }
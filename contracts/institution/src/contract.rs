@@ -1,5 +1,6 @@
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Bytes, Env, Vec
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Bytes, BytesN,
+    Env, Vec,
 };
 
 
@@ -16,6 +17,15 @@ pub enum Error {
 }
 
 
+/// Key an institution signs off-chain certificates with. Stands in for
+/// `Option<BytesN<32>>`, which the SDK cannot store as a struct field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum SigningKey {
+    None,
+    Ed25519(BytesN<32>),
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct Institution {
@@ -25,6 +35,7 @@ pub struct Institution {
     pub verified: bool,
     pub metadata: Bytes,
     pub created_at: u64,
+    pub signing_key: SigningKey,
 }
 
 #[contract]
@@ -43,6 +54,7 @@ impl InstitutionContract {
             verified: false,
             metadata,
             created_at,
+            signing_key: SigningKey::None,
         };
 
         env.storage().persistent().set(&id, &institution);
@@ -82,6 +94,32 @@ impl InstitutionContract {
             .unwrap_or_else(|| panic_with_error!(&env, Error::InstitutionNotFound))
     }
 
+    pub fn is_verified(env: Env, id: u64) -> bool {
+        Self::get_institution(env, id).verified
+    }
+
+    /// Registers the ed25519 public key the institution signs off-chain
+    /// certificates with. Only the institution's wallet can set it.
+    pub fn set_signing_key(env: Env, id: u64, signing_key: BytesN<32>) {
+        let mut institution: Institution = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::InstitutionNotFound));
+
+        institution.wallet.require_auth();
+
+        institution.signing_key = SigningKey::Ed25519(signing_key);
+        env.storage().persistent().set(&id, &institution);
+    }
+
+    pub fn get_signing_key(env: Env, id: u64) -> Option<BytesN<32>> {
+        match Self::get_institution(env, id).signing_key {
+            SigningKey::Ed25519(signing_key) => Some(signing_key),
+            SigningKey::None => None,
+        }
+    }
+
     pub fn remove_institution(env: Env, id: u64, admin: Address) {
         admin.require_auth();
         if !is_admin(&env, &admin) {
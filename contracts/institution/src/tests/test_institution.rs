@@ -1,8 +1,8 @@
 #![cfg(test)]
 
-use soroban_sdk::{testutils::Address as _, Address, Bytes, Env};
+use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env};
 
-use crate::contract::{InstitutionContract, InstitutionContractClient};
+use crate::contract::{InstitutionContract, InstitutionContractClient, SigningKey};
 
 // use crate::{InstitutionContract, InstitutionContractClient};
 
@@ -137,3 +137,39 @@ fn test_set_admin_twice_should_fail() {
     client.set_admin(&admin1);
     client.set_admin(&admin2);
 }
+
+#[test]
+fn test_set_signing_key() {
+    let env = Env::default();
+    let contract_id = env.register(InstitutionContract, ());
+    let client = InstitutionContractClient::new(&env, &contract_id);
+
+    let wallet = Address::generate(&env);
+    let name = Bytes::from_slice(&env, b"Institution");
+    let metadata = Bytes::from_slice(&env, b"Metadata");
+    let signing_key = BytesN::from_array(&env, &[3; 32]);
+
+    let id = client.register_institution(&name, &wallet, &metadata);
+    assert_eq!(client.get_signing_key(&id), None);
+    assert!(!client.is_verified(&id));
+
+    env.mock_all_auths();
+    client.set_signing_key(&id, &signing_key);
+
+    assert_eq!(client.get_signing_key(&id), Some(signing_key.clone()));
+    assert_eq!(
+        client.get_institution(&id).signing_key,
+        SigningKey::Ed25519(signing_key)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_set_signing_key_unknown_institution() {
+    let env = Env::default();
+    let contract_id = env.register(InstitutionContract, ());
+    let client = InstitutionContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.set_signing_key(&42, &BytesN::from_array(&env, &[3; 32]));
+}
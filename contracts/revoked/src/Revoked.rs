@@ -16,7 +16,21 @@ pub struct revoked_certificate {
 }
 
 pub const ADMIN: Symbol = symbol_short!("admin");
-pub const REVOKED_LIST: Symbol = symbol_short!("revoked");
+// Each revocation is its own persistent entry keyed by
+// `(REVOKED, certificate_id)`, so a call only touches the certificates it
+// names. `REVOKED_INDEX` entries keep the revocation order for
+// `All_Revoked_Certificates`, with `REVOKED_COUNT` entries in total.
+pub const REVOKED: Symbol = symbol_short!("revoked");
+pub const REVOKED_INDEX: Symbol = symbol_short!("rvkd_idx");
+pub const REVOKED_COUNT: Symbol = symbol_short!("rvkd_cnt");
+// Prefix for revocations of off-chain signed certificates, whose ids are
+// chosen by each institution and so are only unique per institution.
+pub const SIGNED_REVOKED: Symbol = symbol_short!("sgn_rvkd");
+
+// Upper bound on certificates handled by one `revoke_batch` call so a call
+// stays within the ledger's per-transaction resource limits. Larger
+// cohorts are revoked in several chunks.
+pub const MAX_REVOKE_BATCH: u32 = 50;
 
 #[contractimpl]
 impl RevokedRegistry {
@@ -34,64 +48,142 @@ impl RevokedRegistry {
             panic!("only_admin_can_revoke_certificates");
         }
 
-        let revoked_at = env.ledger().timestamp();
+        if env.storage().persistent().has(&(REVOKED, certificate_id)) {
+            panic!("certificate_already_revoked");
+        }
 
         let new_revoked_certificate = revoked_certificate {
             certificate_id,
             revoked_by: caller.clone(),
             reason,
-            revoked_at,
+            revoked_at: env.ledger().timestamp(),
         };
 
-        let mut revoked_list: Vec<revoked_certificate> =
-            match env.storage().instance().get(&REVOKED_LIST) {
-                Some(x) => x,
-                None => Vec::new(&env),
-            };
-
-        revoked_list.push_back(new_revoked_certificate.clone());
-
-        env.storage().instance().set(&REVOKED_LIST, &revoked_list);
+        store_revocation(&env, &new_revoked_certificate);
 
         env.events()
             .publish(("revoked_certificate", caller), new_revoked_certificate);
     }
 
-    pub fn is_revoked(env: Env, certificate_id: u64) -> bool {
-        let revoked_list: Vec<revoked_certificate> =
-            match env.storage().instance().get(&REVOKED_LIST) {
-                Some(x) => x,
-                None => Vec::new(&env),
-            };
+    pub fn revoke_batch(env: Env, caller: Address, certificate_ids: Vec<u64>, reason: Bytes) -> u32 {
+        caller.require_auth();
 
-        for x in revoked_list.iter() {
-            if x.certificate_id == certificate_id {
-                return true;
+        let admin: Address = env.storage().instance().get(&ADMIN).expect("admin not set");
+        if caller != admin {
+            panic!("only_admin_can_revoke_certificates");
+        }
+
+        if certificate_ids.len() > MAX_REVOKE_BATCH {
+            panic!("batch_too_large");
+        }
+
+        let revoked_at = env.ledger().timestamp();
+
+        let mut revoked_count = 0;
+        for certificate_id in certificate_ids.iter() {
+            // Certificates already in the registry keep their original record
+            if env.storage().persistent().has(&(REVOKED, certificate_id)) {
+                continue;
             }
+
+            let new_revoked_certificate = revoked_certificate {
+                certificate_id,
+                revoked_by: caller.clone(),
+                reason: reason.clone(),
+                revoked_at,
+            };
+
+            store_revocation(&env, &new_revoked_certificate);
+            revoked_count += 1;
+
+            env.events()
+                .publish(("revoked_certificate", caller.clone()), new_revoked_certificate);
         }
 
-        false
+        revoked_count
+    }
+
+    pub fn revoke_signed_certificate(
+        env: Env,
+        caller: Address,
+        institution_id: u64,
+        certificate_id: u64,
+        reason: Bytes,
+    ) {
+        caller.require_auth();
+
+        let admin: Address = env.storage().instance().get(&ADMIN).expect("admin not set");
+        if caller != admin {
+            panic!("only_admin_can_revoke_certificates");
+        }
+
+        let key = (SIGNED_REVOKED, institution_id, certificate_id);
+        if env.storage().persistent().has(&key) {
+            panic!("certificate_already_revoked");
+        }
+
+        let new_revoked_certificate = revoked_certificate {
+            certificate_id,
+            revoked_by: caller.clone(),
+            reason,
+            revoked_at: env.ledger().timestamp(),
+        };
+
+        env.storage().persistent().set(&key, &new_revoked_certificate);
+
+        env.events().publish(
+            ("revoked_signed_certificate", caller, institution_id),
+            new_revoked_certificate,
+        );
+    }
+
+    pub fn is_signed_certificate_revoked(env: Env, institution_id: u64, certificate_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(SIGNED_REVOKED, institution_id, certificate_id))
+    }
+
+    pub fn is_revoked(env: Env, certificate_id: u64) -> bool {
+        env.storage().persistent().has(&(REVOKED, certificate_id))
     }
 
     pub fn All_Revoked_Certificates(env: Env) -> Vec<revoked_certificate> {
-        match env.storage().instance().get(&REVOKED_LIST) {
-            Some(x) => x,
-            None => Vec::new(&env),
+        let count: u32 = env.storage().instance().get(&REVOKED_COUNT).unwrap_or(0);
+
+        let mut revoked_list = Vec::new(&env);
+        for index in 0..count {
+            let certificate_id: u64 = env
+                .storage()
+                .persistent()
+                .get(&(REVOKED_INDEX, index))
+                .unwrap();
+            revoked_list.push_back(
+                env.storage()
+                    .persistent()
+                    .get(&(REVOKED, certificate_id))
+                    .unwrap(),
+            );
         }
+
+        revoked_list
     }
 
     pub fn Get_Revocation_Details(env: Env, certificate_id: u64) -> Option<(Address, Bytes, u64)> {
-        let revoked_list: Vec<revoked_certificate> =
-            match env.storage().instance().get(&REVOKED_LIST) {
-                Some(x) => x,
-                None => Vec::new(&env),
-            };
+        let revoked: revoked_certificate =
+            env.storage().persistent().get(&(REVOKED, certificate_id))?;
 
-        for x in revoked_list.iter() {
-            if x.certificate_id == certificate_id {
-                return Some((x.revoked_by, x.reason, x.revoked_at));
-            }
-        }
-        None
+        Some((revoked.revoked_by, revoked.reason, revoked.revoked_at))
     }
 }
+
+fn store_revocation(env: &Env, revoked: &revoked_certificate) {
+    let count: u32 = env.storage().instance().get(&REVOKED_COUNT).unwrap_or(0);
+
+    env.storage()
+        .persistent()
+        .set(&(REVOKED, revoked.certificate_id), revoked);
+    env.storage()
+        .persistent()
+        .set(&(REVOKED_INDEX, count), &revoked.certificate_id);
+    env.storage().instance().set(&REVOKED_COUNT, &(count + 1));
+}
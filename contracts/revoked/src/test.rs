@@ -1,8 +1,8 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Bytes, Env, Symbol, Vec};
-use Revoked::{RevokedRegistry, RevokedRegistryClient, ADMIN};
+use soroban_sdk::{testutils::{Address as _, Events}, vec, Address, Bytes, Env, Symbol, Vec};
+use Revoked::{RevokedRegistry, RevokedRegistryClient, ADMIN, MAX_REVOKE_BATCH, REVOKED};
 
 #[test]
 fn test_set_admin() {
@@ -110,3 +110,124 @@ fn test_all_revoked_certificates() {
     let revoked_list = client.All_Revoked_Certificates();
     assert_eq!(revoked_list.len(), 2);
 }
+
+#[test]
+fn test_revoke_batch() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let reason = Bytes::from_slice(&env, b"Exam fraud");
+
+    client.Set_admin(&admin);
+    client.Revoke_Certificate(&admin, &201, &Bytes::from_slice(&env, b"Forgery"));
+
+    env.mock_all_auths();
+    let revoked = client.revoke_batch(&admin, &vec![&env, 201, 202, 203, 203], &reason);
+
+    // 201 was already revoked and 203 is listed twice
+    assert_eq!(revoked, 2);
+    assert_eq!(env.events().all().len(), 2);
+    assert_eq!(client.All_Revoked_Certificates().len(), 3);
+    assert!(client.is_revoked(&202));
+    assert!(client.is_revoked(&203));
+
+    let details = client.Get_Revocation_Details(&201).unwrap();
+    assert_eq!(details.1, Bytes::from_slice(&env, b"Forgery"));
+    let details = client.Get_Revocation_Details(&203).unwrap();
+    assert_eq!(details.1, reason);
+    assert_eq!(client.Get_Revocation_Details(&204), None);
+
+    // Each revocation is stored under its own certificate id
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&(REVOKED, 202u64)));
+        assert!(!env.storage().persistent().has(&(REVOKED, 204u64)));
+    });
+}
+
+#[test]
+#[should_panic(expected = "certificate_already_revoked")]
+fn test_revoke_certificate_twice_should_fail() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.Set_admin(&admin);
+
+    client.Revoke_Certificate(&admin, &301, &Bytes::from_slice(&env, b"Forgery"));
+    client.Revoke_Certificate(&admin, &301, &Bytes::from_slice(&env, b"Plagiarism"));
+}
+
+#[test]
+#[should_panic(expected = "batch_too_large")]
+fn test_revoke_batch_over_limit_should_fail() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.Set_admin(&admin);
+
+    let mut certificate_ids = Vec::new(&env);
+    for certificate_id in 0..(MAX_REVOKE_BATCH as u64 + 1) {
+        certificate_ids.push_back(certificate_id);
+    }
+
+    env.mock_all_auths();
+    client.revoke_batch(&admin, &certificate_ids, &Bytes::from_slice(&env, b"Fraud"));
+}
+
+#[test]
+#[should_panic(expected = "only_admin_can_revoke_certificates")]
+fn test_non_admin_cannot_revoke_batch() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let non_admin = Address::generate(&env);
+    client.Set_admin(&admin);
+
+    env.mock_all_auths();
+    client.revoke_batch(&non_admin, &vec![&env, 1, 2], &Bytes::from_slice(&env, b"Fraud"));
+}
+
+#[test]
+fn test_revoke_signed_certificate() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.Set_admin(&admin);
+
+    env.mock_all_auths();
+    client.revoke_signed_certificate(&admin, &1, &42, &Bytes::from_slice(&env, b"Key leaked"));
+
+    assert!(client.is_signed_certificate_revoked(&1, &42));
+
+    // Signed certificate ids are scoped to their institution and do not
+    // overlap with on-chain certificate ids
+    assert!(!client.is_signed_certificate_revoked(&2, &42));
+    assert!(!client.is_revoked(&42));
+
+    client.Revoke_Certificate(&admin, &7, &Bytes::from_slice(&env, b"Forgery"));
+    assert!(!client.is_signed_certificate_revoked(&1, &7));
+}
+
+#[test]
+#[should_panic(expected = "only_admin_can_revoke_certificates")]
+fn test_non_admin_cannot_revoke_signed_certificate() {
+    let env = Env::default();
+    let contract_id = env.register(RevokedRegistry, ());
+    let client = RevokedRegistryClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let not_admin = Address::generate(&env);
+    client.Set_admin(&admin);
+
+    env.mock_all_auths();
+    client.revoke_signed_certificate(&not_admin, &1, &42, &Bytes::from_slice(&env, b"Key leaked"));
+}
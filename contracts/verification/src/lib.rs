@@ -2,10 +2,16 @@
 mod test;
 mod verification;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, Vec};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, String, Vec};
 use verification::{
-    list_verification_requests, revoke_verification_request, submit_verification_request,
-    verify_certificate, VerificationRequest,
+    approve_verification_request, deny_verification_request, get_rate_limit, get_verification_fee,
+    get_verification_request, get_verification_result, list_audit_by_certificate,
+    list_audit_by_requester, list_requests_by_requester, list_verification_requests,
+    reclaim_verification_fee, register_certificate, revoke_grant, revoke_verification_request,
+    set_admin, set_certificate_terms, set_fee_token, set_rate_limit, set_registries,
+    set_verification_fee, submit_verification_request, verify_certificate,
+    verify_signed_certificate, AuditEntry, RateLimit, SignedCertificate, VerificationRequest,
+    VerificationResult, VerificationStatusResult,
 };
 
 #[contract]
@@ -13,7 +19,73 @@ pub struct VerificationContract;
 
 #[contractimpl]
 impl VerificationContract {
-    pub fn verify_certificate(env: Env, certificate_id: u64) -> verification::CertificateDetails {
+    pub fn set_admin(env: Env, admin: Address) {
+        set_admin(env, admin)
+    }
+
+    pub fn set_registries(
+        env: Env,
+        admin: Address,
+        institution_contract: Address,
+        revocation_registry: Address,
+    ) {
+        set_registries(env, admin, institution_contract, revocation_registry)
+    }
+
+    pub fn set_fee_token(env: Env, admin: Address, fee_token: Address) {
+        set_fee_token(env, admin, fee_token)
+    }
+
+    pub fn set_rate_limit(env: Env, admin: Address, max_requests: u32, window: u64, deposit: i128) {
+        set_rate_limit(env, admin, max_requests, window, deposit)
+    }
+
+    pub fn get_rate_limit(env: Env) -> Option<RateLimit> {
+        get_rate_limit(env)
+    }
+
+    pub fn set_verification_fee(env: Env, institution: Address, fee: i128) {
+        set_verification_fee(env, institution, fee)
+    }
+
+    pub fn get_verification_fee(env: Env, institution: Address) -> i128 {
+        get_verification_fee(env, institution)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_certificate(
+        env: Env,
+        issuer: Address,
+        certificate_id: u64,
+        holder: Address,
+        student: String,
+        course: String,
+        institution: String,
+        issuance_date: u64,
+    ) {
+        register_certificate(
+            env,
+            issuer,
+            certificate_id,
+            holder,
+            student,
+            course,
+            institution,
+            issuance_date,
+        )
+    }
+
+    pub fn set_certificate_terms(
+        env: Env,
+        issuer: Address,
+        certificate_id: u64,
+        institution_id: u64,
+        expires_at: Option<u64>,
+    ) {
+        set_certificate_terms(env, issuer, certificate_id, institution_id, expires_at)
+    }
+
+    pub fn verify_certificate(env: Env, certificate_id: u64) -> VerificationStatusResult {
         verify_certificate(env, certificate_id)
     }
 
@@ -25,7 +97,71 @@ impl VerificationContract {
         list_verification_requests(env, certificate_id)
     }
 
+    pub fn get_verification_request(env: Env, request_id: u64) -> VerificationRequest {
+        get_verification_request(env, request_id)
+    }
+
+    pub fn list_requests_by_requester(
+        env: Env,
+        requester: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<VerificationRequest> {
+        list_requests_by_requester(env, requester, start, limit)
+    }
+
+    pub fn list_audit_by_certificate(
+        env: Env,
+        certificate_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<AuditEntry> {
+        list_audit_by_certificate(env, certificate_id, start, limit)
+    }
+
+    pub fn list_audit_by_requester(
+        env: Env,
+        requester: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<AuditEntry> {
+        list_audit_by_requester(env, requester, start, limit)
+    }
+
     pub fn revoke_verification_request(env: Env, admin: Address, request_id: u64) {
         revoke_verification_request(env, admin, request_id)
     }
+
+    pub fn approve_verification_request(env: Env, holder: Address, request_id: u64, duration: u64) {
+        approve_verification_request(env, holder, request_id, duration)
+    }
+
+    pub fn reclaim_verification_fee(env: Env, requester: Address, request_id: u64) {
+        reclaim_verification_fee(env, requester, request_id)
+    }
+
+    pub fn revoke_grant(env: Env, holder: Address, request_id: u64) {
+        revoke_grant(env, holder, request_id)
+    }
+
+    pub fn deny_verification_request(env: Env, holder: Address, request_id: u64) {
+        deny_verification_request(env, holder, request_id)
+    }
+
+    pub fn get_verification_result(
+        env: Env,
+        requester: Address,
+        request_id: u64,
+    ) -> VerificationResult {
+        get_verification_result(env, requester, request_id)
+    }
+
+    pub fn verify_signed_certificate(
+        env: Env,
+        payload: Bytes,
+        signature: BytesN<64>,
+        institution_id: u64,
+    ) -> SignedCertificate {
+        verify_signed_certificate(env, payload, signature, institution_id)
+    }
 }
@@ -1,16 +1,171 @@
 #![cfg(test)]
+// The revocation registry mock mirrors that contract's non-snake-case names.
+#![allow(non_snake_case)]
 extern crate std;
 
-use crate::verification::{register_certificate, DataKey};
+use crate::verification::{
+    AuditAction, DataKey, RequestStatus, SignedCertificate, VerificationStatus,
+    PERSISTENT_TTL_EXTEND, REQUEST_TTL,
+};
 use crate::{VerificationContract, VerificationContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, panic_with_error,
+    testutils::{
+        storage::{Persistent as _, Temporary as _},
+        Address as _, Ledger,
+    },
+    token::{StellarAssetClient, TokenClient},
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, String, Symbol,
+};
 use std::string::ToString;
 
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum InstitutionError {
+    InstitutionNotFound = 1,
+}
+
+// Mock institution contract exposing verification state and signing keys
+#[contract]
+pub struct InstitutionContractMock;
+
+#[contractimpl]
+impl InstitutionContractMock {
+    pub fn set_institution(env: Env, id: u64, verified: bool, signing_key: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&(id, Symbol::new(&env, "verified")), &verified);
+        env.storage().instance().set(&id, &signing_key);
+    }
+
+    pub fn remove_institution(env: Env, id: u64) {
+        env.storage()
+            .instance()
+            .remove(&(id, Symbol::new(&env, "verified")));
+    }
+
+    // Fails with `InstitutionNotFound` for unknown ids like the real contract
+    pub fn is_verified(env: Env, id: u64) -> bool {
+        env.storage()
+            .instance()
+            .get(&(id, Symbol::new(&env, "verified")))
+            .unwrap_or_else(|| panic_with_error!(&env, InstitutionError::InstitutionNotFound))
+    }
+
+    pub fn get_signing_key(env: Env, id: u64) -> Option<BytesN<32>> {
+        env.storage().instance().get(&id)
+    }
+}
+
+// Mock revocation registry
+#[contract]
+pub struct RevocationRegistryMock;
+
+#[contractimpl]
+impl RevocationRegistryMock {
+    pub fn revoke(env: Env, certificate_id: u64, reason: Bytes) {
+        env.storage().instance().set(&certificate_id, &reason);
+    }
+
+    pub fn is_revoked(env: Env, certificate_id: u64) -> bool {
+        env.storage().instance().has(&certificate_id)
+    }
+
+    pub fn revoke_signed_certificate(env: Env, institution_id: u64, certificate_id: u64) {
+        env.storage()
+            .instance()
+            .set(&(institution_id, certificate_id), &true);
+    }
+
+    pub fn is_signed_certificate_revoked(
+        env: Env,
+        institution_id: u64,
+        certificate_id: u64,
+    ) -> bool {
+        env.storage()
+            .instance()
+            .has(&(institution_id, certificate_id))
+    }
+
+    pub fn Get_Revocation_Details(env: Env, certificate_id: u64) -> Option<(Address, Bytes, u64)> {
+        env.storage()
+            .instance()
+            .get(&certificate_id)
+            .map(|reason| (env.current_contract_address(), reason, 0))
+    }
+}
+
+struct SignedSetup<'a> {
+    env: Env,
+    client: VerificationContractClient<'a>,
+    institution: InstitutionContractMockClient<'a>,
+    registry: RevocationRegistryMockClient<'a>,
+    signing_key: SigningKey,
+}
+
+fn setup_signed() -> SignedSetup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(VerificationContract, ());
+    let client = VerificationContractClient::new(&env, &contract_id);
+
+    let institution_id = env.register(InstitutionContractMock, ());
+    let institution = InstitutionContractMockClient::new(&env, &institution_id);
+    let registry_id = env.register(RevocationRegistryMock, ());
+    let registry = RevocationRegistryMockClient::new(&env, &registry_id);
+
+    client.set_admin(&admin);
+    client.set_registries(&admin, &institution_id, &registry_id);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    institution.set_institution(&1, &true, &public_key);
+
+    SignedSetup {
+        env,
+        client,
+        institution,
+        registry,
+        signing_key,
+    }
+}
+
+fn sign_certificate(
+    env: &Env,
+    signing_key: &SigningKey,
+    certificate: &SignedCertificate,
+) -> (Bytes, BytesN<64>) {
+    let payload = certificate.clone().to_xdr(env);
+    let mut message = std::vec![0u8; payload.len() as usize];
+    payload.copy_into_slice(&mut message);
+    let signature = signing_key.sign(&message).to_bytes();
+
+    (payload, BytesN::from_array(env, &signature))
+}
+
+fn signed_certificate(env: &Env) -> SignedCertificate {
+    SignedCertificate {
+        certificate_id: 42,
+        institution_id: 1,
+        student: Address::generate(env),
+        course_id: 7,
+        issued_at: 1677609600,
+        metadata: Bytes::from_slice(env, b"Blockchain Development"),
+    }
+}
+
 #[test]
 fn test_certificate_verification() {
     // Set up test environment
     let env = Env::default();
     let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let issuer = Address::generate(&env);
     let requester = Address::generate(&env);
 
     // Deploy contract
@@ -25,30 +180,38 @@ fn test_certificate_verification() {
 
     // Register a test certificate
     let cert_id = 1234u64;
-    env.as_contract(&contract_id, || {
-        register_certificate(
-            &env,
-            cert_id,
-            String::from_str(&env, "John Doe"),
-            String::from_str(&env, "Blockchain Development"),
-            String::from_str(&env, "Stellar University"),
-            1677609600, // March 1, 2023
-        );
-    });
+    env.mock_all_auths();
+    client.register_certificate(
+        &issuer,
+        &cert_id,
+        &holder,
+        &String::from_str(&env, "John Doe"),
+        &String::from_str(&env, "Blockchain Development"),
+        &String::from_str(&env, "Stellar University"),
+        &1677609600, // March 1, 2023
+    );
 
     // Verify the certificate exists
-    let cert_details = client.verify_certificate(&cert_id);
-    assert_eq!(cert_details.student.to_string(), "John Doe");
-    assert_eq!(cert_details.course.to_string(), "Blockchain Development");
-    assert_eq!(cert_details.institution.to_string(), "Stellar University");
-    assert_eq!(cert_details.issuance_date, 1677609600);
-    assert!(cert_details.valid);
+    let result = client.verify_certificate(&cert_id);
+    assert_eq!(result.status, VerificationStatus::Valid);
+    assert_eq!(result.revocation_reason, None);
 
     // Submit verification request
     env.mock_all_auths();
     let request_id = client.submit_verification_request(&cert_id, &requester);
     assert_eq!(request_id, 1);
 
+    // Details are only released to the requester once the holder approves
+    client.approve_verification_request(&holder, &request_id, &3600);
+    let cert_details = client
+        .get_verification_result(&requester, &request_id)
+        .details;
+    assert_eq!(cert_details.student.to_string(), "John Doe");
+    assert_eq!(cert_details.course.to_string(), "Blockchain Development");
+    assert_eq!(cert_details.institution.to_string(), "Stellar University");
+    assert_eq!(cert_details.issuance_date, 1677609600);
+    assert!(cert_details.valid);
+
     // List verification requests
     let requests = client.list_verification_requests(&cert_id);
     assert_eq!(requests.len(), 1);
@@ -70,9 +233,54 @@ fn test_nonexistent_certificate() {
     let client = VerificationContractClient::new(&env, &contract_id);
 
     // Try to verify a non-existent certificate
-    let cert_details = client.verify_certificate(&9999u64);
-    assert!(!cert_details.valid);
-    assert_eq!(cert_details.student.to_string(), "");
+    let result = client.verify_certificate(&9999u64);
+    assert_eq!(result.status, VerificationStatus::NotFound);
+    assert_eq!(result.revocation_reason, None);
+}
+
+#[test]
+fn test_register_certificate_requires_issuer() {
+    let env = Env::default();
+    let contract_id = env.register(VerificationContract, ());
+    let client = VerificationContractClient::new(&env, &contract_id);
+    let issuer = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let name = String::from_str(&env, "Jane Doe");
+
+    // Without the issuer's signature nothing is registered
+    let result =
+        client.try_register_certificate(&issuer, &1234, &holder, &name, &name, &name, &1677609600);
+    assert!(result.is_err());
+    assert_eq!(
+        client.verify_certificate(&1234).status,
+        VerificationStatus::NotFound
+    );
+
+    env.mock_all_auths();
+    client.register_certificate(&issuer, &1234, &holder, &name, &name, &name, &1677609600);
+    assert_eq!(
+        client.verify_certificate(&1234).status,
+        VerificationStatus::Valid
+    );
+}
+
+#[test]
+#[should_panic(expected = "Certificate already registered")]
+fn test_register_certificate_twice() {
+    let setup = setup_request();
+    let name = String::from_str(&setup.env, "John Doe");
+
+    // Another issuer cannot take over a registered certificate
+    let other_issuer = Address::generate(&setup.env);
+    setup.client.register_certificate(
+        &other_issuer,
+        &1234,
+        &setup.requester,
+        &name,
+        &name,
+        &name,
+        &1677609600,
+    );
 }
 
 #[test]
@@ -96,6 +304,8 @@ fn test_unauthorized_revocation() {
     let env = Env::default();
     let admin = Address::generate(&env);
     let not_admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let issuer = Address::generate(&env);
     let requester = Address::generate(&env);
 
     // Deploy contract
@@ -110,16 +320,16 @@ fn test_unauthorized_revocation() {
 
     // Register certificate
     let cert_id = 1234u64;
-    env.as_contract(&contract_id, || {
-        register_certificate(
-            &env,
-            cert_id,
-            String::from_str(&env, "Jane Doe"),
-            String::from_str(&env, "Smart Contracts"),
-            String::from_str(&env, "Stellar University"),
-            1677609600,
-        );
-    });
+    env.mock_all_auths();
+    client.register_certificate(
+        &issuer,
+        &cert_id,
+        &holder,
+        &String::from_str(&env, "Jane Doe"),
+        &String::from_str(&env, "Smart Contracts"),
+        &String::from_str(&env, "Stellar University"),
+        &1677609600,
+    );
 
     // Submit verification request
     env.mock_all_auths();
@@ -129,3 +339,764 @@ fn test_unauthorized_revocation() {
     client.revoke_verification_request(&not_admin, &request_id);
     // Should panic with "Only admin can revoke verification requests"
 }
+
+#[test]
+fn test_verify_signed_certificate() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let (payload, signature) = sign_certificate(&setup.env, &setup.signing_key, &certificate);
+
+    let verified = setup
+        .client
+        .verify_signed_certificate(&payload, &signature, &1);
+    assert_eq!(verified, certificate);
+}
+
+#[test]
+fn test_verify_signed_certificate_bad_signature() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let other_key = SigningKey::from_bytes(&[9u8; 32]);
+    let (payload, signature) = sign_certificate(&setup.env, &other_key, &certificate);
+
+    let result = setup
+        .client
+        .try_verify_signed_certificate(&payload, &signature, &1);
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Certificate has been revoked")]
+fn test_verify_signed_certificate_revoked() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let (payload, signature) = sign_certificate(&setup.env, &setup.signing_key, &certificate);
+
+    setup
+        .registry
+        .revoke_signed_certificate(&1, &certificate.certificate_id);
+    setup
+        .client
+        .verify_signed_certificate(&payload, &signature, &1);
+}
+
+#[test]
+fn test_signed_revocations_are_scoped_to_institution() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let (payload, signature) = sign_certificate(&setup.env, &setup.signing_key, &certificate);
+
+    // Revoking the on-chain certificate or another institution's signed
+    // certificate with the same id leaves this one valid
+    setup.registry.revoke(
+        &certificate.certificate_id,
+        &Bytes::from_slice(&setup.env, b"fraud"),
+    );
+    setup
+        .registry
+        .revoke_signed_certificate(&2, &certificate.certificate_id);
+
+    let verified = setup
+        .client
+        .verify_signed_certificate(&payload, &signature, &1);
+    assert_eq!(verified, certificate);
+}
+
+#[test]
+#[should_panic(expected = "Institution is not verified")]
+fn test_verify_signed_certificate_unverified_institution() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let (payload, signature) = sign_certificate(&setup.env, &setup.signing_key, &certificate);
+
+    let public_key = BytesN::from_array(&setup.env, &setup.signing_key.verifying_key().to_bytes());
+    setup.institution.set_institution(&1, &false, &public_key);
+    setup
+        .client
+        .verify_signed_certificate(&payload, &signature, &1);
+}
+
+#[test]
+#[should_panic(expected = "Certificate was issued by another institution")]
+fn test_verify_signed_certificate_wrong_institution() {
+    let setup = setup_signed();
+    let certificate = signed_certificate(&setup.env);
+    let (payload, signature) = sign_certificate(&setup.env, &setup.signing_key, &certificate);
+
+    setup
+        .client
+        .verify_signed_certificate(&payload, &signature, &2);
+}
+
+const GRANT: u64 = 24 * 60 * 60;
+
+struct RequestSetup<'a> {
+    env: Env,
+    client: VerificationContractClient<'a>,
+    admin: Address,
+    holder: Address,
+    issuer: Address,
+    requester: Address,
+    request_id: u64,
+    token: TokenClient<'a>,
+}
+
+fn setup_request() -> RequestSetup<'static> {
+    setup_request_with_fee(0)
+}
+
+// Registers certificate 1234 and submits a request for it. The requester
+// starts with 1000 units of the fee token.
+fn setup_request_with_fee(fee: i128) -> RequestSetup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let issuer = Address::generate(&env);
+    let requester = Address::generate(&env);
+
+    let contract_id = env.register(VerificationContract, ());
+    let client = VerificationContractClient::new(&env, &contract_id);
+    client.set_admin(&admin);
+
+    client.register_certificate(
+        &issuer,
+        &1234,
+        &holder,
+        &String::from_str(&env, "Jane Doe"),
+        &String::from_str(&env, "Smart Contracts"),
+        &String::from_str(&env, "Stellar University"),
+        &1677609600,
+    );
+
+    let token_admin = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token_id).mint(&requester, &1000);
+    client.set_fee_token(&admin, &token_id);
+    client.set_verification_fee(&issuer, &fee);
+
+    let request_id = client.submit_verification_request(&1234, &requester);
+
+    RequestSetup {
+        token: TokenClient::new(&env, &token_id),
+        env,
+        client,
+        admin,
+        holder,
+        issuer,
+        requester,
+        request_id,
+    }
+}
+
+#[test]
+fn test_holder_approves_request() {
+    let setup = setup_request();
+
+    let request = setup
+        .client
+        .list_verification_requests(&1234)
+        .get(0)
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Pending);
+    assert_eq!(request.decided_at, None);
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+
+    let request = setup
+        .client
+        .list_verification_requests(&1234)
+        .get(0)
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Approved);
+
+    let result = setup
+        .client
+        .get_verification_result(&setup.requester, &setup.request_id);
+    assert_eq!(result.details.course.to_string(), "Smart Contracts");
+    assert!(result.details.valid);
+}
+
+#[test]
+#[should_panic(expected = "Request has not been approved")]
+fn test_result_hidden_until_approved() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .get_verification_result(&setup.requester, &setup.request_id);
+}
+
+#[test]
+#[should_panic(expected = "Request has not been approved")]
+fn test_denied_request_hides_result() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .deny_verification_request(&setup.holder, &setup.request_id);
+    assert_eq!(
+        setup
+            .client
+            .list_verification_requests(&1234)
+            .get(0)
+            .unwrap()
+            .status,
+        RequestStatus::Denied
+    );
+
+    setup
+        .client
+        .get_verification_result(&setup.requester, &setup.request_id);
+}
+
+#[test]
+#[should_panic(expected = "Only the certificate holder can respond to requests")]
+fn test_only_holder_can_approve() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .approve_verification_request(&setup.requester, &setup.request_id, &GRANT);
+}
+
+#[test]
+#[should_panic(expected = "Request has already been decided")]
+fn test_request_decided_once() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .deny_verification_request(&setup.holder, &setup.request_id);
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+}
+
+#[test]
+#[should_panic(expected = "Request has expired")]
+fn test_pending_request_expires() {
+    let setup = setup_request();
+
+    setup
+        .env
+        .ledger()
+        .set_timestamp(setup.env.ledger().timestamp() + REQUEST_TTL + 1);
+    assert_eq!(
+        setup
+            .client
+            .list_verification_requests(&1234)
+            .get(0)
+            .unwrap()
+            .status,
+        RequestStatus::Expired
+    );
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+}
+
+#[test]
+fn test_grant_expires() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+    let request = setup
+        .client
+        .list_verification_requests(&1234)
+        .get(0)
+        .unwrap();
+    assert_eq!(
+        request.expires_at,
+        Some(setup.env.ledger().timestamp() + GRANT)
+    );
+
+    setup
+        .env
+        .ledger()
+        .set_timestamp(setup.env.ledger().timestamp() + GRANT);
+    assert_eq!(
+        setup
+            .client
+            .list_verification_requests(&1234)
+            .get(0)
+            .unwrap()
+            .status,
+        RequestStatus::Expired
+    );
+
+    let result = setup
+        .client
+        .try_get_verification_result(&setup.requester, &setup.request_id);
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Request has not been approved")]
+fn test_holder_revokes_grant_early() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+    setup.client.revoke_grant(&setup.holder, &setup.request_id);
+
+    let request = setup
+        .client
+        .list_verification_requests(&1234)
+        .get(0)
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Expired);
+    assert_eq!(request.expires_at, Some(setup.env.ledger().timestamp()));
+
+    setup
+        .client
+        .get_verification_result(&setup.requester, &setup.request_id);
+}
+
+#[test]
+#[should_panic(expected = "Request has no active grant")]
+fn test_revoke_grant_requires_approval() {
+    let setup = setup_request();
+
+    setup.client.revoke_grant(&setup.holder, &setup.request_id);
+}
+
+#[test]
+#[should_panic(expected = "Grant duration must be positive")]
+fn test_grant_duration_must_be_positive() {
+    let setup = setup_request();
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &0);
+}
+
+#[test]
+#[should_panic(expected = "Grant duration is too long")]
+fn test_grant_duration_must_not_overflow() {
+    let setup = setup_request();
+    setup.env.ledger().set_timestamp(1);
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &u64::MAX);
+}
+
+#[test]
+fn test_verification_fee_paid_on_approval() {
+    let setup = setup_request_with_fee(100);
+
+    assert_eq!(setup.client.get_verification_fee(&setup.issuer), 100);
+    assert_eq!(setup.token.balance(&setup.requester), 900);
+    assert_eq!(setup.token.balance(&setup.client.address), 100);
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+
+    let request = setup
+        .client
+        .list_verification_requests(&1234)
+        .get(0)
+        .unwrap();
+    assert_eq!(request.fee, 100);
+    assert!(request.fee_settled);
+    assert_eq!(setup.token.balance(&setup.issuer), 100);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+}
+
+#[test]
+fn test_verification_fee_refunded_on_denial() {
+    let setup = setup_request_with_fee(100);
+
+    setup
+        .client
+        .deny_verification_request(&setup.holder, &setup.request_id);
+
+    assert_eq!(setup.token.balance(&setup.requester), 1000);
+    assert_eq!(setup.token.balance(&setup.issuer), 0);
+}
+
+#[test]
+fn test_verification_fee_reclaimed_after_expiry() {
+    let setup = setup_request_with_fee(100);
+
+    setup
+        .env
+        .ledger()
+        .set_timestamp(setup.env.ledger().timestamp() + REQUEST_TTL + 1);
+    setup
+        .client
+        .reclaim_verification_fee(&setup.requester, &setup.request_id);
+    assert_eq!(setup.token.balance(&setup.requester), 1000);
+
+    let result = setup
+        .client
+        .try_reclaim_verification_fee(&setup.requester, &setup.request_id);
+    assert!(result.is_err());
+    assert_eq!(setup.token.balance(&setup.requester), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance for verification fee")]
+fn test_verification_fee_requires_balance() {
+    setup_request_with_fee(5000);
+}
+
+#[test]
+#[should_panic(expected = "Fee must not be negative")]
+fn test_verification_fee_must_not_be_negative() {
+    setup_request_with_fee(-1);
+}
+
+#[test]
+fn test_list_requests_by_requester() {
+    let setup = setup_request();
+    let other_requester = Address::generate(&setup.env);
+
+    let second = setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    let third = setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    setup
+        .client
+        .submit_verification_request(&1234, &other_requester);
+
+    let requests = setup
+        .client
+        .list_requests_by_requester(&setup.requester, &0, &10);
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests.get(0).unwrap().request_id, setup.request_id);
+
+    let page = setup
+        .client
+        .list_requests_by_requester(&setup.requester, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().request_id, second);
+
+    let past_end = setup
+        .client
+        .list_requests_by_requester(&setup.requester, &5, &10);
+    assert_eq!(past_end.len(), 0);
+
+    let request = setup.client.get_verification_request(&third);
+    assert_eq!(request.requester, setup.requester);
+    assert_eq!(request.status, RequestStatus::Pending);
+}
+
+#[test]
+#[should_panic(expected = "Verification request does not exist")]
+fn test_get_missing_verification_request() {
+    let setup = setup_request();
+
+    setup.client.get_verification_request(&99);
+}
+
+#[test]
+fn test_requests_kept_in_persistent_storage() {
+    let setup = setup_request();
+
+    setup.env.as_contract(&setup.client.address, || {
+        let persistent = setup.env.storage().persistent();
+        for key in [
+            DataKey::VerificationRequest(setup.request_id),
+            DataKey::CertificateRequests(1234),
+            DataKey::RequesterRequests(setup.requester.clone()),
+        ] {
+            assert!(!setup.env.storage().instance().has(&key));
+            assert_eq!(persistent.get_ttl(&key), PERSISTENT_TTL_EXTEND);
+        }
+    });
+}
+
+#[test]
+fn test_audit_log_survives_revocation() {
+    let setup = setup_request();
+    let other_requester = Address::generate(&setup.env);
+
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &setup.request_id, &GRANT);
+    setup.client.revoke_grant(&setup.holder, &setup.request_id);
+    let other_request = setup
+        .client
+        .submit_verification_request(&1234, &other_requester);
+    setup
+        .client
+        .revoke_verification_request(&setup.admin, &setup.request_id);
+
+    assert_eq!(setup.client.list_verification_requests(&1234).len(), 1);
+
+    let log = setup.client.list_audit_by_certificate(&1234, &0, &10);
+    assert_eq!(log.len(), 5);
+    let actions = [
+        (AuditAction::Submitted, &setup.requester),
+        (AuditAction::Approved, &setup.holder),
+        (AuditAction::GrantRevoked, &setup.holder),
+        (AuditAction::Submitted, &other_requester),
+        (AuditAction::Revoked, &setup.admin),
+    ];
+    for (i, (action, actor)) in actions.iter().enumerate() {
+        let entry = log.get(i as u32).unwrap();
+        assert_eq!(entry.action, *action);
+        assert_eq!(entry.actor, **actor);
+    }
+    assert_eq!(log.get(4).unwrap().request_id, setup.request_id);
+
+    let requester_log = setup
+        .client
+        .list_audit_by_requester(&setup.requester, &0, &10);
+    assert_eq!(requester_log.len(), 4);
+    assert!(requester_log
+        .iter()
+        .all(|entry| entry.request_id == setup.request_id));
+
+    let other_log = setup
+        .client
+        .list_audit_by_requester(&other_requester, &0, &10);
+    assert_eq!(other_log.len(), 1);
+    assert_eq!(other_log.get(0).unwrap().request_id, other_request);
+
+    let page = setup.client.list_audit_by_certificate(&1234, &3, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().actor, other_requester);
+
+    // Entries and both indexes live in persistent storage with a renewed TTL
+    setup.env.as_contract(&setup.client.address, || {
+        let persistent = setup.env.storage().persistent();
+        for key in [
+            DataKey::AuditEntry(1),
+            DataKey::CertificateAudit(1234),
+            DataKey::RequesterAudit(setup.requester.clone()),
+        ] {
+            assert_eq!(persistent.get_ttl(&key), PERSISTENT_TTL_EXTEND);
+        }
+        assert!(!setup.env.storage().instance().has(&DataKey::AuditEntry(1)));
+    });
+}
+
+// Registers certificate 1234, issued by institution 1, on a contract whose
+// registries are the mocks from `setup_signed`.
+fn setup_status(expires_at: Option<u64>) -> SignedSetup<'static> {
+    let setup = setup_signed();
+    let holder = Address::generate(&setup.env);
+    let issuer = Address::generate(&setup.env);
+
+    setup.client.register_certificate(
+        &issuer,
+        &1234,
+        &holder,
+        &String::from_str(&setup.env, "Jane Doe"),
+        &String::from_str(&setup.env, "Smart Contracts"),
+        &String::from_str(&setup.env, "Stellar University"),
+        &1677609600,
+    );
+    setup
+        .client
+        .set_certificate_terms(&issuer, &1234, &1, &expires_at);
+
+    setup
+}
+
+#[test]
+#[should_panic(expected = "Only the issuer can set certificate terms")]
+fn test_only_issuer_sets_certificate_terms() {
+    let setup = setup_status(None);
+    let stranger = Address::generate(&setup.env);
+
+    setup
+        .client
+        .set_certificate_terms(&stranger, &1234, &2, &None);
+}
+
+#[test]
+fn test_verify_certificate_valid() {
+    let setup = setup_status(None);
+
+    let result = setup.client.verify_certificate(&1234);
+    assert_eq!(result.status, VerificationStatus::Valid);
+    assert_eq!(result.revocation_reason, None);
+}
+
+#[test]
+fn test_verify_certificate_revoked_with_reason() {
+    let setup = setup_status(None);
+    let reason = Bytes::from_slice(&setup.env, b"academic misconduct");
+
+    setup.registry.revoke(&1234, &reason);
+
+    let result = setup.client.verify_certificate(&1234);
+    assert_eq!(result.status, VerificationStatus::Revoked);
+    assert_eq!(result.revocation_reason, Some(reason));
+}
+
+#[test]
+fn test_verify_certificate_expired() {
+    let setup = setup_status(Some(1_000));
+
+    assert_eq!(
+        setup.client.verify_certificate(&1234).status,
+        VerificationStatus::Valid
+    );
+
+    setup.env.ledger().set_timestamp(1_000);
+    assert_eq!(
+        setup.client.verify_certificate(&1234).status,
+        VerificationStatus::Expired
+    );
+}
+
+#[test]
+fn test_verify_certificate_institution_status() {
+    let setup = setup_status(None);
+    let public_key = BytesN::from_array(&setup.env, &setup.signing_key.verifying_key().to_bytes());
+
+    setup.institution.set_institution(&1, &false, &public_key);
+    assert_eq!(
+        setup.client.verify_certificate(&1234).status,
+        VerificationStatus::InstitutionUnverified
+    );
+
+    setup.institution.remove_institution(&1);
+    assert_eq!(
+        setup.client.verify_certificate(&1234).status,
+        VerificationStatus::InstitutionRemoved
+    );
+}
+
+#[test]
+#[should_panic(expected = "Institution contract call failed")]
+fn test_institution_call_failure_is_not_removal() {
+    let setup = setup_status(None);
+    let admin = Address::generate(&setup.env);
+    let verification = setup.env.register(VerificationContract, ());
+    let client = VerificationContractClient::new(&setup.env, &verification);
+
+    // The revocation registry has no `is_verified`, so the call fails for a
+    // reason other than the institution being unknown
+    client.set_admin(&admin);
+    client.set_registries(&admin, &setup.registry.address, &setup.registry.address);
+    client.register_certificate(
+        &admin,
+        &1234,
+        &admin,
+        &String::from_str(&setup.env, "Jane Doe"),
+        &String::from_str(&setup.env, "Smart Contracts"),
+        &String::from_str(&setup.env, "Stellar University"),
+        &1677609600,
+    );
+    client.set_certificate_terms(&admin, &1234, &1, &None);
+
+    client.verify_certificate(&1234);
+}
+
+#[test]
+fn test_rate_limit_per_window() {
+    let setup = setup_request();
+    let other_requester = Address::generate(&setup.env);
+
+    setup.client.set_rate_limit(&setup.admin, &2, &3600, &0);
+    assert_eq!(setup.client.get_rate_limit().unwrap().max_requests, 2);
+
+    // The request from setup predates the limit and is not counted
+    setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    let result = setup
+        .client
+        .try_submit_verification_request(&1234, &setup.requester);
+    assert!(result.is_err());
+
+    // Quotas are tracked per requester
+    setup
+        .client
+        .submit_verification_request(&1234, &other_requester);
+
+    // A new window resets the quota
+    setup
+        .env
+        .ledger()
+        .set_timestamp(setup.env.ledger().timestamp() + 3600);
+    setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+}
+
+#[test]
+fn test_rate_limit_window_kept_in_temporary_storage() {
+    let setup = setup_request();
+    setup.env.ledger().set_timestamp(1);
+
+    // A window reaching past the end of time neither overflows nor resets
+    setup.client.set_rate_limit(&setup.admin, &1, &u64::MAX, &0);
+    setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    let result = setup
+        .client
+        .try_submit_verification_request(&1234, &setup.requester);
+    assert!(result.is_err());
+
+    setup.env.as_contract(&setup.client.address, || {
+        let key = DataKey::RequesterWindow(setup.requester.clone());
+        assert!(!setup.env.storage().instance().has(&key));
+        assert_eq!(
+            setup.env.storage().temporary().get_ttl(&key),
+            setup.env.storage().max_ttl()
+        );
+    });
+}
+
+#[test]
+fn test_rate_limit_deposit_refunded() {
+    let setup = setup_request_with_fee(100);
+
+    setup.client.set_rate_limit(&setup.admin, &5, &3600, &50);
+    let request_id = setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    assert_eq!(setup.token.balance(&setup.requester), 750);
+    assert_eq!(
+        setup.client.get_verification_request(&request_id).deposit,
+        50
+    );
+
+    // Approval pays the fee to the issuer and returns the deposit
+    setup
+        .client
+        .approve_verification_request(&setup.holder, &request_id, &GRANT);
+    assert_eq!(setup.token.balance(&setup.requester), 800);
+    assert_eq!(setup.token.balance(&setup.issuer), 100);
+
+    // Revoking a pending request refunds both the fee and the deposit
+    let request_id = setup
+        .client
+        .submit_verification_request(&1234, &setup.requester);
+    assert_eq!(setup.token.balance(&setup.requester), 650);
+    setup
+        .client
+        .revoke_verification_request(&setup.admin, &request_id);
+    assert_eq!(setup.token.balance(&setup.requester), 800);
+}
+
+#[test]
+#[should_panic(expected = "Rate limit must allow at least one request per window")]
+fn test_rate_limit_requires_quota() {
+    let setup = setup_request();
+
+    setup.client.set_rate_limit(&setup.admin, &0, &3600, &0);
+}
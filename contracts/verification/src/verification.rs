@@ -1,4 +1,32 @@
-use soroban_sdk::{contracttype, Address, Env, String, Vec};
+use soroban_sdk::{
+    contracttype, token, vec, xdr::FromXdr, Address, Bytes, BytesN, Env, IntoVal, String, Symbol,
+    Vec,
+};
+
+/// Pending requests the holder has not answered within this many seconds
+/// are treated as expired.
+pub const REQUEST_TTL: u64 = 7 * 24 * 60 * 60;
+
+/// `InstitutionNotFound` error code of the institution contract.
+const INSTITUTION_NOT_FOUND: u32 = 1;
+
+/// Roughly one day of ledgers at five seconds per ledger.
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Requests, their indexes and audit entries live in persistent storage and
+/// are kept alive for this many ledgers, renewed whenever they are written
+/// or read.
+pub const PERSISTENT_TTL_EXTEND: u32 = 90 * DAY_IN_LEDGERS;
+const PERSISTENT_TTL_THRESHOLD: u32 = PERSISTENT_TTL_EXTEND - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RequestStatus {
+    Pending,
+    Approved,
+    Denied,
+    Expired,
+}
 
 #[contracttype]
 #[derive(Clone)]
@@ -7,6 +35,55 @@ pub struct VerificationRequest {
     pub certificate_id: u64,
     pub requester: Address,
     pub timestamp: u64,
+    pub status: RequestStatus,
+    pub decided_at: Option<u64>,
+    pub expires_at: Option<u64>,    // end of access for approved requests
+    pub fee_token: Option<Address>, // token the fee and deposit were escrowed in, if any
+    pub fee: i128,
+    pub deposit: i128,     // anti-spam deposit returned once the request is settled
+    pub fee_settled: bool, // fee paid to the issuer or refunded, deposit returned
+}
+
+/// Per-requester quota on `submit_verification_request`, with an optional
+/// deposit held until the request is decided, revoked or reclaimed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    pub max_requests: u32,
+    pub window: u64, // seconds
+    pub deposit: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RequesterWindow {
+    pub window_start: u64,
+    pub count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditAction {
+    Submitted,
+    Approved,
+    Denied,
+    GrantRevoked,
+    FeeReclaimed,
+    Revoked,
+}
+
+/// Entry in the append-only audit log. Entries are never updated or
+/// removed, so they outlive revoked requests.
+#[contracttype]
+#[derive(Clone)]
+pub struct AuditEntry {
+    pub entry_id: u64,
+    pub request_id: u64,
+    pub certificate_id: u64,
+    pub requester: Address,
+    pub actor: Address,
+    pub action: AuditAction,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -17,15 +94,220 @@ pub struct CertificateDetails {
     pub institution: String,
     pub issuance_date: u64,
     pub valid: bool,
+    pub institution_id: Option<u64>, // id in the institution contract, if known
+    pub expires_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerificationStatus {
+    Valid,
+    NotFound,
+    Revoked,
+    Expired,
+    InstitutionUnverified,
+    InstitutionRemoved,
+}
+
+/// Public outcome of `verify_certificate`. It carries no certificate
+/// details; those are only released through `get_verification_result`.
+/// `revocation_reason` is only set for revoked certificates.
+#[contracttype]
+#[derive(Clone)]
+pub struct VerificationStatusResult {
+    pub status: VerificationStatus,
+    pub revocation_reason: Option<Bytes>,
+}
+
+/// Outcome of `get_verification_result`. `details` is empty when the status
+/// is `NotFound`; `revocation_reason` is only set for revoked certificates.
+#[contracttype]
+#[derive(Clone)]
+pub struct VerificationResult {
+    pub status: VerificationStatus,
+    pub details: CertificateDetails,
+    pub revocation_reason: Option<Bytes>,
+}
+
+/// Certificate issued and signed off-chain by an institution. The signed
+/// payload is the XDR encoding of this struct.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedCertificate {
+    pub certificate_id: u64,
+    pub institution_id: u64,
+    pub student: Address,
+    pub course_id: u64,
+    pub issued_at: u64,
+    pub metadata: Bytes,
 }
 
 #[contracttype]
 pub enum DataKey {
     Admin,
     NextRequestId,
-    VerificationRequest(u64), // request_id -> VerificationRequest
-    CertificateRequests(u64), // certificate_id -> Vec<request_id>
+    VerificationRequest(u64), // request_id -> VerificationRequest (persistent)
+    CertificateRequests(u64), // certificate_id -> Vec<request_id> (persistent)
+    RequesterRequests(Address), // requester -> Vec<request_id> (persistent)
     Certificate(u64),         // certificate_id -> CertificateDetails
+    CertificateHolder(u64),   // certificate_id -> Address of the holder
+    CertificateIssuer(u64),   // certificate_id -> Address of the issuing institution
+    FeeToken,                 // Address of the token verification fees are paid in
+    VerificationFee(Address), // institution -> fee charged per request
+    RateLimit,
+    RequesterWindow(Address), // requester -> RequesterWindow (temporary)
+    NextAuditId,
+    AuditEntry(u64),         // entry_id -> AuditEntry (persistent)
+    CertificateAudit(u64),   // certificate_id -> Vec<entry_id> (persistent)
+    RequesterAudit(Address), // requester -> Vec<entry_id> (persistent)
+    InstitutionContract,     // Address of the institution contract
+    RevocationRegistry,      // Address of the revoked certificates registry
+}
+
+pub fn set_admin(env: Env, admin: Address) {
+    if env.storage().instance().has(&DataKey::Admin) {
+        panic!("Admin already set");
+    }
+
+    env.storage().instance().set(&DataKey::Admin, &admin);
+}
+
+pub fn set_registries(
+    env: Env,
+    admin: Address,
+    institution_contract: Address,
+    revocation_registry: Address,
+) {
+    require_admin(&env, &admin);
+
+    env.storage()
+        .instance()
+        .set(&DataKey::InstitutionContract, &institution_contract);
+    env.storage()
+        .instance()
+        .set(&DataKey::RevocationRegistry, &revocation_registry);
+}
+
+pub fn set_fee_token(env: Env, admin: Address, fee_token: Address) {
+    require_admin(&env, &admin);
+
+    env.storage().instance().set(&DataKey::FeeToken, &fee_token);
+}
+
+pub fn set_rate_limit(env: Env, admin: Address, max_requests: u32, window: u64, deposit: i128) {
+    require_admin(&env, &admin);
+
+    if max_requests == 0 || window == 0 {
+        panic!("Rate limit must allow at least one request per window");
+    }
+
+    if deposit < 0 {
+        panic!("Deposit must not be negative");
+    }
+
+    if deposit > 0 && !env.storage().instance().has(&DataKey::FeeToken) {
+        panic!("Fee token not configured");
+    }
+
+    let rate_limit = RateLimit {
+        max_requests,
+        window,
+        deposit,
+    };
+    env.storage()
+        .instance()
+        .set(&DataKey::RateLimit, &rate_limit);
+}
+
+pub fn get_rate_limit(env: Env) -> Option<RateLimit> {
+    env.storage().instance().get(&DataKey::RateLimit)
+}
+
+pub fn set_verification_fee(env: Env, institution: Address, fee: i128) {
+    institution.require_auth();
+
+    if fee < 0 {
+        panic!("Fee must not be negative");
+    }
+
+    if fee > 0 && !env.storage().instance().has(&DataKey::FeeToken) {
+        panic!("Fee token not configured");
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKey::VerificationFee(institution), &fee);
+}
+
+pub fn get_verification_fee(env: Env, institution: Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::VerificationFee(institution))
+        .unwrap_or(0)
+}
+
+pub fn verify_signed_certificate(
+    env: Env,
+    payload: Bytes,
+    signature: BytesN<64>,
+    institution_id: u64,
+) -> SignedCertificate {
+    let certificate = SignedCertificate::from_xdr(&env, &payload)
+        .unwrap_or_else(|_| panic!("Malformed certificate payload"));
+
+    if certificate.institution_id != institution_id {
+        panic!("Certificate was issued by another institution");
+    }
+
+    let institution_contract: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::InstitutionContract)
+        .expect("Registries not configured");
+    let revocation_registry: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::RevocationRegistry)
+        .expect("Registries not configured");
+
+    let institution_args = vec![&env, institution_id.into_val(&env)];
+
+    let verified: bool = env.invoke_contract(
+        &institution_contract,
+        &Symbol::new(&env, "is_verified"),
+        institution_args.clone(),
+    );
+    if !verified {
+        panic!("Institution is not verified");
+    }
+
+    let signing_key: Option<BytesN<32>> = env.invoke_contract(
+        &institution_contract,
+        &Symbol::new(&env, "get_signing_key"),
+        institution_args,
+    );
+    let signing_key = signing_key.unwrap_or_else(|| panic!("Institution has no signing key"));
+
+    // Traps the invocation if the signature does not match
+    env.crypto()
+        .ed25519_verify(&signing_key, &payload, &signature);
+
+    // Off-chain ids are only unique per institution, so revocations are
+    // looked up by both ids rather than in the on-chain certificate namespace
+    let revoked: bool = env.invoke_contract(
+        &revocation_registry,
+        &Symbol::new(&env, "is_signed_certificate_revoked"),
+        vec![
+            &env,
+            institution_id.into_val(&env),
+            certificate.certificate_id.into_val(&env),
+        ],
+    );
+    if revoked {
+        panic!("Certificate has been revoked");
+    }
+
+    certificate
 }
 
 pub fn submit_verification_request(env: Env, certificate_id: u64, requester: Address) -> u64 {
@@ -37,6 +319,12 @@ pub fn submit_verification_request(env: Env, certificate_id: u64, requester: Add
         panic!("Certificate does not exist");
     }
 
+    // Enforce the requester's quota for the current window
+    let deposit = consume_request_quota(&env, &requester);
+
+    // Escrow the issuing institution's fee and the deposit until the holder decides
+    let (fee_token, fee) = escrow_fee(&env, certificate_id, &requester, deposit);
+
     // Get next request ID
     let request_id = get_next_request_id(&env);
 
@@ -46,74 +334,319 @@ pub fn submit_verification_request(env: Env, certificate_id: u64, requester: Add
         certificate_id,
         requester: requester.clone(),
         timestamp: env.ledger().timestamp(),
+        status: RequestStatus::Pending,
+        decided_at: None,
+        expires_at: None,
+        fee_token,
+        fee,
+        deposit,
+        fee_settled: false,
     };
 
     // Store verification request
+    store_request(&env, &request);
+
+    // Add request ID to certificate's request list
+    let cert_requests_key = DataKey::CertificateRequests(certificate_id);
+    let mut cert_requests = get_request_ids(&env, &cert_requests_key);
+    cert_requests.push_back(request_id);
+    store_request_ids(&env, &cert_requests_key, &cert_requests);
+
+    // Add request ID to requester's request list
+    let requester_requests_key = DataKey::RequesterRequests(requester.clone());
+    let mut requester_requests = get_request_ids(&env, &requester_requests_key);
+    requester_requests.push_back(request_id);
+    store_request_ids(&env, &requester_requests_key, &requester_requests);
+
+    record_audit(&env, &request, &requester, AuditAction::Submitted);
+
+    request_id
+}
+
+/// Registers a certificate so it can be verified and requested. The
+/// issuer authorizes the registration and becomes the only address that can
+/// set the certificate's terms and receive its verification fees.
+#[allow(clippy::too_many_arguments)]
+pub fn register_certificate(
+    env: Env,
+    issuer: Address,
+    certificate_id: u64,
+    holder: Address,
+    student: String,
+    course: String,
+    institution: String,
+    issuance_date: u64,
+) {
+    issuer.require_auth();
+
+    if certificate_exists(&env, certificate_id) {
+        panic!("Certificate already registered");
+    }
+
+    let certificate = CertificateDetails {
+        student,
+        course,
+        institution,
+        issuance_date,
+        valid: true,
+        institution_id: None,
+        expires_at: None,
+    };
+
+    env.storage()
+        .instance()
+        .set(&DataKey::Certificate(certificate_id), &certificate);
+    env.storage()
+        .instance()
+        .set(&DataKey::CertificateHolder(certificate_id), &holder);
     env.storage()
         .instance()
-        .set(&DataKey::VerificationRequest(request_id), &request);
+        .set(&DataKey::CertificateIssuer(certificate_id), &issuer);
+}
 
-    // Add request ID to certificate's request list
-    let mut cert_requests: Vec<u64> = env
+/// Records which institution issued a certificate and when it expires, so
+/// `verify_certificate` can check both. Only the certificate's issuer can
+/// set them.
+pub fn set_certificate_terms(
+    env: Env,
+    issuer: Address,
+    certificate_id: u64,
+    institution_id: u64,
+    expires_at: Option<u64>,
+) {
+    issuer.require_auth();
+
+    let mut certificate: CertificateDetails = env
         .storage()
         .instance()
-        .get(&DataKey::CertificateRequests(certificate_id))
-        .unwrap_or_else(|| Vec::new(&env));
+        .get(&DataKey::Certificate(certificate_id))
+        .expect("Certificate does not exist");
 
-    cert_requests.push_back(request_id);
-    env.storage().instance().set(
-        &DataKey::CertificateRequests(certificate_id),
-        &cert_requests,
-    );
+    let stored_issuer: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::CertificateIssuer(certificate_id))
+        .unwrap();
+    if stored_issuer != issuer {
+        panic!("Only the issuer can set certificate terms");
+    }
 
-    request_id
+    certificate.institution_id = Some(institution_id);
+    certificate.expires_at = expires_at;
+
+    env.storage()
+        .instance()
+        .set(&DataKey::Certificate(certificate_id), &certificate);
 }
 
-pub fn verify_certificate(env: Env, certificate_id: u64) -> CertificateDetails {
+pub fn verify_certificate(env: Env, certificate_id: u64) -> VerificationStatusResult {
+    let result = check_certificate(&env, certificate_id);
+
+    VerificationStatusResult {
+        status: result.status,
+        revocation_reason: result.revocation_reason,
+    }
+}
+
+fn check_certificate(env: &Env, certificate_id: u64) -> VerificationResult {
     // Check if certificate exists
-    if !certificate_exists(&env, certificate_id) {
-        return CertificateDetails {
-            student: String::from_str(&env, ""),
-            course: String::from_str(&env, ""),
-            institution: String::from_str(&env, ""),
-            issuance_date: 0,
-            valid: false,
+    if !certificate_exists(env, certificate_id) {
+        return VerificationResult {
+            status: VerificationStatus::NotFound,
+            details: CertificateDetails {
+                student: String::from_str(env, ""),
+                course: String::from_str(env, ""),
+                institution: String::from_str(env, ""),
+                issuance_date: 0,
+                valid: false,
+                institution_id: None,
+                expires_at: None,
+            },
+            revocation_reason: None,
         };
     }
 
     // Get certificate details
-    let certificate_details: CertificateDetails = env
+    let mut certificate_details: CertificateDetails = env
         .storage()
         .instance()
         .get(&DataKey::Certificate(certificate_id))
         .unwrap();
 
-    certificate_details
+    let revocation_reason = get_revocation_reason(env, certificate_id);
+
+    let status = if revocation_reason.is_some() || !certificate_details.valid {
+        VerificationStatus::Revoked
+    } else if certificate_details
+        .expires_at
+        .is_some_and(|expires_at| env.ledger().timestamp() >= expires_at)
+    {
+        VerificationStatus::Expired
+    } else {
+        get_institution_status(env, certificate_details.institution_id)
+    };
+
+    certificate_details.valid = status == VerificationStatus::Valid;
+
+    VerificationResult {
+        status,
+        details: certificate_details,
+        revocation_reason,
+    }
 }
 
 pub fn list_verification_requests(env: Env, certificate_id: u64) -> Vec<VerificationRequest> {
     // Get request IDs for certificate
-    let request_ids: Vec<u64> = env
-        .storage()
-        .instance()
-        .get(&DataKey::CertificateRequests(certificate_id))
-        .unwrap_or_else(|| Vec::new(&env));
+    let request_ids = get_request_ids(&env, &DataKey::CertificateRequests(certificate_id));
 
     // Get request details
     let mut requests: Vec<VerificationRequest> = Vec::new(&env);
     for i in 0..request_ids.len() {
         let request_id = request_ids.get(i).unwrap();
-        let request: VerificationRequest = env
-            .storage()
-            .instance()
-            .get(&DataKey::VerificationRequest(request_id))
-            .unwrap();
-        requests.push_back(request);
+        requests.push_back(with_current_status(&env, get_request(&env, request_id)));
     }
 
     requests
 }
 
+pub fn get_verification_request(env: Env, request_id: u64) -> VerificationRequest {
+    with_current_status(&env, get_request(&env, request_id))
+}
+
+/// Returns up to `limit` requests made by `requester`, skipping the first
+/// `start`, in submission order.
+pub fn list_requests_by_requester(
+    env: Env,
+    requester: Address,
+    start: u32,
+    limit: u32,
+) -> Vec<VerificationRequest> {
+    let request_ids = get_request_ids(&env, &DataKey::RequesterRequests(requester));
+
+    let mut requests: Vec<VerificationRequest> = Vec::new(&env);
+    let end = request_ids.len().min(start.saturating_add(limit));
+    for i in start..end {
+        let request_id = request_ids.get(i).unwrap();
+        requests.push_back(with_current_status(&env, get_request(&env, request_id)));
+    }
+
+    requests
+}
+
+/// Returns up to `limit` audit entries for a certificate, skipping the
+/// first `start`, oldest first.
+pub fn list_audit_by_certificate(
+    env: Env,
+    certificate_id: u64,
+    start: u32,
+    limit: u32,
+) -> Vec<AuditEntry> {
+    load_audit_page(
+        &env,
+        &DataKey::CertificateAudit(certificate_id),
+        start,
+        limit,
+    )
+}
+
+/// Returns up to `limit` audit entries for requests made by `requester`,
+/// skipping the first `start`, oldest first.
+pub fn list_audit_by_requester(
+    env: Env,
+    requester: Address,
+    start: u32,
+    limit: u32,
+) -> Vec<AuditEntry> {
+    load_audit_page(&env, &DataKey::RequesterAudit(requester), start, limit)
+}
+
+pub fn approve_verification_request(env: Env, holder: Address, request_id: u64, duration: u64) {
+    if duration == 0 {
+        panic!("Grant duration must be positive");
+    }
+
+    let expires_at = env
+        .ledger()
+        .timestamp()
+        .checked_add(duration)
+        .unwrap_or_else(|| panic!("Grant duration is too long"));
+    decide_request(
+        &env,
+        &holder,
+        request_id,
+        RequestStatus::Approved,
+        Some(expires_at),
+    );
+}
+
+pub fn deny_verification_request(env: Env, holder: Address, request_id: u64) {
+    decide_request(&env, &holder, request_id, RequestStatus::Denied, None);
+}
+
+/// Refunds the fee and deposit of a pending request that lapsed without an
+/// answer.
+pub fn reclaim_verification_fee(env: Env, requester: Address, request_id: u64) {
+    requester.require_auth();
+
+    let mut request = with_current_status(&env, get_request(&env, request_id));
+
+    if request.requester != requester {
+        panic!("Only the requester can reclaim the fee");
+    }
+
+    if request.status != RequestStatus::Expired || request.decided_at.is_some() {
+        panic!("Only unanswered expired requests can be refunded");
+    }
+
+    if request.fee_settled {
+        panic!("Fee already settled");
+    }
+
+    settle_fee(&env, &mut request, &requester);
+
+    store_request(&env, &request);
+
+    record_audit(&env, &request, &requester, AuditAction::FeeReclaimed);
+}
+
+pub fn revoke_grant(env: Env, holder: Address, request_id: u64) {
+    holder.require_auth();
+
+    let mut request = with_current_status(&env, get_request(&env, request_id));
+    require_holder(&env, &holder, request.certificate_id);
+
+    if request.status != RequestStatus::Approved {
+        panic!("Request has no active grant");
+    }
+
+    request.status = RequestStatus::Expired;
+    request.expires_at = Some(env.ledger().timestamp());
+
+    store_request(&env, &request);
+
+    record_audit(&env, &request, &holder, AuditAction::GrantRevoked);
+}
+
+pub fn get_verification_result(
+    env: Env,
+    requester: Address,
+    request_id: u64,
+) -> VerificationResult {
+    requester.require_auth();
+
+    let request = with_current_status(&env, get_request(&env, request_id));
+
+    if request.requester != requester {
+        panic!("Only the requester can read the result");
+    }
+
+    if request.status != RequestStatus::Approved {
+        panic!("Request has not been approved");
+    }
+
+    check_certificate(&env, request.certificate_id)
+}
+
 pub fn revoke_verification_request(env: Env, admin: Address, request_id: u64) {
     // Require admin authorization
     admin.require_auth();
@@ -126,41 +659,382 @@ pub fn revoke_verification_request(env: Env, admin: Address, request_id: u64) {
     }
 
     // Get verification request
-    let request: VerificationRequest = env
+    let mut request = get_request(&env, request_id);
+
+    // Return any fee still held in escrow
+    let requester = request.requester.clone();
+    settle_fee(&env, &mut request, &requester);
+
+    // Remove request from the certificate's and requester's request lists
+    remove_request_id(
+        &env,
+        &DataKey::CertificateRequests(request.certificate_id),
+        request_id,
+    );
+    remove_request_id(
+        &env,
+        &DataKey::RequesterRequests(request.requester.clone()),
+        request_id,
+    );
+
+    // Remove verification request; the audit log keeps the evidence
+    env.storage()
+        .persistent()
+        .remove(&DataKey::VerificationRequest(request_id));
+
+    record_audit(&env, &request, &admin, AuditAction::Revoked);
+}
+
+// Helper functions
+fn decide_request(
+    env: &Env,
+    holder: &Address,
+    request_id: u64,
+    decision: RequestStatus,
+    expires_at: Option<u64>,
+) {
+    holder.require_auth();
+
+    let mut request = with_current_status(env, get_request(env, request_id));
+    require_holder(env, holder, request.certificate_id);
+
+    if request.status == RequestStatus::Expired {
+        panic!("Request has expired");
+    }
+
+    if request.status != RequestStatus::Pending {
+        panic!("Request has already been decided");
+    }
+
+    request.status = decision;
+    request.decided_at = Some(env.ledger().timestamp());
+    request.expires_at = expires_at;
+
+    // Approval pays the issuing institution; denial refunds the requester
+    let recipient = if decision == RequestStatus::Approved {
+        env.storage()
+            .instance()
+            .get(&DataKey::CertificateIssuer(request.certificate_id))
+            .expect("Certificate issuer not set")
+    } else {
+        request.requester.clone()
+    };
+    settle_fee(env, &mut request, &recipient);
+
+    store_request(env, &request);
+
+    let action = if decision == RequestStatus::Approved {
+        AuditAction::Approved
+    } else {
+        AuditAction::Denied
+    };
+    record_audit(env, &request, holder, action);
+}
+
+// Counts a submission against the requester's window and returns the
+// deposit to hold, or zero when no rate limit is configured.
+fn consume_request_quota(env: &Env, requester: &Address) -> i128 {
+    let Some(rate_limit) = get_rate_limit(env.clone()) else {
+        return 0;
+    };
+
+    let now = env.ledger().timestamp();
+    let key = DataKey::RequesterWindow(requester.clone());
+    let mut window: RequesterWindow =
+        env.storage()
+            .temporary()
+            .get(&key)
+            .unwrap_or(RequesterWindow {
+                window_start: now,
+                count: 0,
+            });
+
+    if now >= window.window_start.saturating_add(rate_limit.window) {
+        window = RequesterWindow {
+            window_start: now,
+            count: 0,
+        };
+    }
+
+    if window.count >= rate_limit.max_requests {
+        panic!("Rate limit exceeded");
+    }
+
+    window.count += 1;
+    env.storage().temporary().set(&key, &window);
+
+    // Ledgers close at most once per second, so a TTL of `window` ledgers
+    // keeps the entry for at least the whole window
+    let ttl = u32::try_from(rate_limit.window)
+        .unwrap_or(u32::MAX)
+        .min(env.storage().max_ttl());
+    env.storage().temporary().extend_ttl(&key, ttl, ttl);
+
+    rate_limit.deposit
+}
+
+// Transfers the issuing institution's fee plus `deposit` from the requester
+// into the contract, returning the token and the fee held.
+fn escrow_fee(
+    env: &Env,
+    certificate_id: u64,
+    requester: &Address,
+    deposit: i128,
+) -> (Option<Address>, i128) {
+    let issuer: Option<Address> = env
         .storage()
         .instance()
-        .get(&DataKey::VerificationRequest(request_id))
-        .unwrap();
+        .get(&DataKey::CertificateIssuer(certificate_id));
+    let fee = match issuer {
+        Some(issuer) => get_verification_fee(env.clone(), issuer),
+        None => 0,
+    };
+
+    if fee == 0 && deposit == 0 {
+        return (None, 0);
+    }
+
+    let fee_token: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::FeeToken)
+        .expect("Fee token not configured");
+
+    let token_client = token::Client::new(env, &fee_token);
+    if token_client.balance(requester) < fee + deposit {
+        panic!("Insufficient balance for verification fee and deposit");
+    }
+    token_client.transfer(requester, &env.current_contract_address(), &(fee + deposit));
+
+    (Some(fee_token), fee)
+}
+
+// Pays the fee to `recipient` and returns the deposit to the requester.
+fn settle_fee(env: &Env, request: &mut VerificationRequest, recipient: &Address) {
+    if request.fee_settled {
+        return;
+    }
+
+    if let Some(fee_token) = &request.fee_token {
+        let token_client = token::Client::new(env, fee_token);
+        if request.fee > 0 {
+            token_client.transfer(&env.current_contract_address(), recipient, &request.fee);
+        }
+        if request.deposit > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &request.requester,
+                &request.deposit,
+            );
+        }
+    }
+
+    request.fee_settled = true;
+}
 
-    // Remove request from certificate's request list
-    let cert_requests: Vec<u64> = env
+fn require_holder(env: &Env, holder: &Address, certificate_id: u64) {
+    let stored_holder: Address = env
         .storage()
         .instance()
-        .get(&DataKey::CertificateRequests(request.certificate_id))
-        .unwrap_or_else(|| Vec::new(&env));
+        .get(&DataKey::CertificateHolder(certificate_id))
+        .expect("Certificate holder not set");
+
+    if stored_holder != *holder {
+        panic!("Only the certificate holder can respond to requests");
+    }
+}
+
+fn get_request(env: &Env, request_id: u64) -> VerificationRequest {
+    let key = DataKey::VerificationRequest(request_id);
+    let request = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .expect("Verification request does not exist");
+    extend_persistent_ttl(env, &key);
+
+    request
+}
+
+fn store_request(env: &Env, request: &VerificationRequest) {
+    let key = DataKey::VerificationRequest(request.request_id);
+    env.storage().persistent().set(&key, request);
+    extend_persistent_ttl(env, &key);
+}
+
+fn get_request_ids(env: &Env, key: &DataKey) -> Vec<u64> {
+    match env.storage().persistent().get(key) {
+        Some(request_ids) => {
+            extend_persistent_ttl(env, key);
+            request_ids
+        }
+        None => Vec::new(env),
+    }
+}
+
+fn store_request_ids(env: &Env, key: &DataKey, request_ids: &Vec<u64>) {
+    env.storage().persistent().set(key, request_ids);
+    extend_persistent_ttl(env, key);
+}
+
+// Expiry is derived from the ledger time rather than stored, so pending
+// requests and grants lapse without anyone having to touch them.
+fn with_current_status(env: &Env, mut request: VerificationRequest) -> VerificationRequest {
+    let now = env.ledger().timestamp();
+    let expired = match request.status {
+        RequestStatus::Pending => now > request.timestamp + REQUEST_TTL,
+        RequestStatus::Approved => request
+            .expires_at
+            .is_some_and(|expires_at| now >= expires_at),
+        RequestStatus::Denied | RequestStatus::Expired => false,
+    };
+
+    if expired {
+        request.status = RequestStatus::Expired;
+    }
+
+    request
+}
+
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+
+    let stored_admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("Admin not set");
+
+    if stored_admin != *admin {
+        panic!("Only admin can change the configuration");
+    }
+}
+
+fn remove_request_id(env: &Env, key: &DataKey, request_id: u64) {
+    let requests = get_request_ids(env, key);
 
     // Filter out the revoked request
-    let mut new_requests: Vec<u64> = Vec::new(&env);
-    for i in 0..cert_requests.len() {
-        let id = cert_requests.get(i).unwrap();
+    let mut new_requests: Vec<u64> = Vec::new(env);
+    for i in 0..requests.len() {
+        let id = requests.get(i).unwrap();
         if id != request_id {
             new_requests.push_back(id);
         }
     }
 
-    // Update certificate requests
-    env.storage().instance().set(
-        &DataKey::CertificateRequests(request.certificate_id),
-        &new_requests,
-    );
+    store_request_ids(env, key, &new_requests);
+}
 
-    // Remove verification request
+fn record_audit(env: &Env, request: &VerificationRequest, actor: &Address, action: AuditAction) {
+    let entry_id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::NextAuditId)
+        .unwrap_or(1);
     env.storage()
         .instance()
-        .remove(&DataKey::VerificationRequest(request_id));
+        .set(&DataKey::NextAuditId, &(entry_id + 1));
+
+    let entry = AuditEntry {
+        entry_id,
+        request_id: request.request_id,
+        certificate_id: request.certificate_id,
+        requester: request.requester.clone(),
+        actor: actor.clone(),
+        action,
+        timestamp: env.ledger().timestamp(),
+    };
+    let entry_key = DataKey::AuditEntry(entry_id);
+    env.storage().persistent().set(&entry_key, &entry);
+    extend_persistent_ttl(env, &entry_key);
+
+    for key in [
+        DataKey::CertificateAudit(request.certificate_id),
+        DataKey::RequesterAudit(request.requester.clone()),
+    ] {
+        let mut entry_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        entry_ids.push_back(entry_id);
+        env.storage().persistent().set(&key, &entry_ids);
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+fn load_audit_page(env: &Env, key: &DataKey, start: u32, limit: u32) -> Vec<AuditEntry> {
+    let entry_ids: Vec<u64> = match env.storage().persistent().get(key) {
+        Some(entry_ids) => {
+            extend_persistent_ttl(env, key);
+            entry_ids
+        }
+        None => return Vec::new(env),
+    };
+
+    let mut entries = Vec::new(env);
+    let end = entry_ids.len().min(start.saturating_add(limit));
+    for i in start..end {
+        let entry_key = DataKey::AuditEntry(entry_ids.get(i).unwrap());
+        let entry: AuditEntry = env.storage().persistent().get(&entry_key).unwrap();
+        extend_persistent_ttl(env, &entry_key);
+        entries.push_back(entry);
+    }
+
+    entries
+}
+
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+}
+
+// Looks up the reason in the revocation registry. Certificates revoked
+// without a configured registry have no reason to report.
+fn get_revocation_reason(env: &Env, certificate_id: u64) -> Option<Bytes> {
+    let revocation_registry: Address =
+        env.storage().instance().get(&DataKey::RevocationRegistry)?;
+
+    let details: Option<(Address, Bytes, u64)> = env.invoke_contract(
+        &revocation_registry,
+        &Symbol::new(env, "Get_Revocation_Details"),
+        vec![env, certificate_id.into_val(env)],
+    );
+
+    details.map(|(_, reason, _)| reason)
+}
+
+// The institution contract fails with `InstitutionNotFound` for unknown ids,
+// which means the institution has been removed. Any other failure is not a
+// statement about the institution and aborts the verification.
+fn get_institution_status(env: &Env, institution_id: Option<u64>) -> VerificationStatus {
+    let institution_contract: Option<Address> =
+        env.storage().instance().get(&DataKey::InstitutionContract);
+
+    let (Some(institution_contract), Some(institution_id)) = (institution_contract, institution_id)
+    else {
+        return VerificationStatus::Valid;
+    };
+
+    let verified = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+        &institution_contract,
+        &Symbol::new(env, "is_verified"),
+        vec![env, institution_id.into_val(env)],
+    );
+
+    match verified {
+        Ok(Ok(true)) => VerificationStatus::Valid,
+        Ok(Ok(false)) => VerificationStatus::InstitutionUnverified,
+        Err(Ok(error))
+            if error == soroban_sdk::Error::from_contract_error(INSTITUTION_NOT_FOUND) =>
+        {
+            VerificationStatus::InstitutionRemoved
+        }
+        _ => panic!("Institution contract call failed"),
+    }
 }
 
-// Helper functions
 fn certificate_exists(env: &Env, certificate_id: u64) -> bool {
     env.storage()
         .instance()
@@ -181,27 +1055,3 @@ fn get_next_request_id(env: &Env) -> u64 {
 
     id
 }
-
-// Certificate management functions (normally would be in another contract)
-// Added here to support testing and functionality
-
-pub fn register_certificate(
-    env: &Env,
-    certificate_id: u64,
-    student: String,
-    course: String,
-    institution: String,
-    issuance_date: u64,
-) {
-    let certificate = CertificateDetails {
-        student,
-        course,
-        institution,
-        issuance_date,
-        valid: true,
-    };
-
-    env.storage()
-        .instance()
-        .set(&DataKey::Certificate(certificate_id), &certificate);
-}